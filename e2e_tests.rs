@@ -0,0 +1,709 @@
+//! End-to-end tests for the WLUNES contract, run against a sandboxed runtime.
+//!
+//! Enable them with `cargo test --features e2e-tests`. The contracts are
+//! compiled to Wasm first, so the `wasm32-unknown-unknown` target and the
+//! `rust-src` component must be installed.
+
+use crate::traits::*;
+use crate::wlunes::*;
+use flash_borrower::flash_borrower::{FlashBorrowerRef, Repayment};
+use ink::primitives::{AccountId, Hash};
+use ink_e2e::{account_id, AccountKeyring, CallDryRunResult, ChainBackend, ContractsBackend};
+use psp22_receiver::psp22_receiver::{Psp22Receiver, Psp22ReceiverRef};
+use router::router::{Router, RouterRef};
+use scale::Encode;
+
+type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Native LUNES wrapped by the test accounts.
+const AMOUNT: Balance = 1_000_000_000;
+
+/// Flash loan size used by the tests.
+const LOAN: Balance = 500_000_000;
+
+/// Flash fee used by the tests (1%).
+const FEE_BPS: u16 = 100;
+
+/// Security limits used by the tests. The sandbox does not advance the block
+/// timestamp between calls, so the per-account cooldown is disabled.
+fn e2e_config() -> SecurityConfig {
+    SecurityConfig {
+        transaction_cooldown: 0,
+        ..SecurityConfig::default()
+    }
+}
+
+/// Checks that the dry run of `operation` succeeded within the weight
+/// `recommended_weight` gives for it.
+fn assert_covered<V: scale::Decode>(operation: Operation, result: &CallDryRunResult<ink::env::DefaultEnvironment, V>) {
+    assert!(!result.exec_return_value().did_revert(), "{operation:?} failed");
+    let required = result.exec_result.gas_required;
+    let recommended = operation.recommended_weight();
+    assert!(
+        required.ref_time() <= recommended.ref_time && required.proof_size() <= recommended.proof_size,
+        "{operation:?} needs {required:?}, recommended {recommended:?}"
+    );
+}
+
+/// ECDSA key and the account it signs for. ECDSA is the more expensive
+/// signature scheme to verify.
+fn ecdsa_signer(seed: u8) -> (secp256k1::SecretKey, AccountId) {
+    let secret = secp256k1::SecretKey::from_slice(&[seed; 32]).unwrap();
+    let public = secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &secret);
+    let mut account = [0u8; 32];
+    ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public.serialize(), &mut account);
+    (secret, AccountId::from(account))
+}
+
+/// Signs the blake2-256 hash of the SCALE-encoded `payload` with `secret`.
+fn sign_ecdsa(secret: &secp256k1::SecretKey, payload: &impl Encode) -> Signature {
+    let mut message = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(payload, &mut message);
+    let (recovery_id, compact) = secp256k1::SECP256K1
+        .sign_ecdsa_recoverable(&secp256k1::Message::from_digest(message), secret)
+        .serialize_compact();
+    let mut signature = [0u8; 65];
+    signature[..64].copy_from_slice(&compact);
+    signature[64] = recovery_id.to_i32() as u8;
+    Signature::Ecdsa(signature)
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn receiver_accepts_transfer<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = Psp22ReceiverRef::new(wlunes.account_id);
+    let receiver = client
+        .instantiate("psp22_receiver", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("receiver instantiate failed");
+    let receiver_calls = receiver.call_builder::<Psp22Receiver>();
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    let transfer = token.transfer(receiver.account_id, 100, b"memo".to_vec());
+    let result = client.call(&ink_e2e::alice(), &transfer).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    // The hook ran and recorded the transfer
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(receiver.account_id)).dry_run().await?;
+    assert_eq!(balance.return_value(), 100);
+    let received = client.call(&ink_e2e::alice(), &receiver_calls.received_from(alice)).dry_run().await?;
+    assert_eq!(received.return_value(), 100);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn receiver_rejection_reason_surfaces<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    // The receiver only accepts another token, so it rejects WLUNES
+    let other_token = AccountId::from([0x01; 32]);
+    let mut constructor = Psp22ReceiverRef::new(other_token);
+    let receiver = client
+        .instantiate("psp22_receiver", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("receiver instantiate failed");
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    let transfer = token.transfer(receiver.account_id, 100, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &transfer).submit().await?;
+    assert_eq!(
+        result.return_value(),
+        Err(PSP22Error::SafeTransferCheckFailed("Unsupported token".into()))
+    );
+
+    // The transfer reverted
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(receiver.account_id)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(alice)).dry_run().await?;
+    assert_eq!(balance.return_value(), AMOUNT);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn flash_loan_is_repaid_with_fee<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Full);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    // The borrower holds just the fee
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    assert!(fee > 0);
+    let fund = token.deposit_for(borrower.account_id, fee);
+    let fund = client.call(&ink_e2e::alice(), &fund).value(fee).submit().await?;
+    assert_eq!(fund.return_value(), Ok(()));
+
+    let loan = token.flash_loan(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &loan).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    // The loan is burned, the fee went to the fee recipient
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT + fee);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(borrower.account_id)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), fee);
+    let allowance = token.allowance(borrower.account_id, wlunes.account_id);
+    let allowance = client.call(&ink_e2e::alice(), &allowance).dry_run().await?;
+    assert_eq!(allowance.return_value(), 0);
+    let minted = client.call(&ink_e2e::alice(), &token.flash_minted()).dry_run().await?;
+    assert_eq!(minted.return_value(), 0);
+
+    // Every WLUNES is still backed: both holders can unwrap everything
+    let withdraw = client.call(&ink_e2e::alice(), &token.withdraw(AMOUNT)).submit().await?;
+    assert_eq!(withdraw.return_value(), Ok(()));
+    let withdraw = client.call(&ink_e2e::bob(), &token.withdraw(fee)).submit().await?;
+    assert_eq!(withdraw.return_value(), Ok(()));
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), 0);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn flash_loan_short_repayment_reverts<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Short);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    let fund = token.deposit_for(borrower.account_id, fee);
+    let fund = client.call(&ink_e2e::alice(), &fund).value(fee).submit().await?;
+    assert_eq!(fund.return_value(), Ok(()));
+
+    // The borrower approves one unit less than the loan plus the fee
+    let loan = token.flash_loan(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &loan).submit().await?;
+    assert_eq!(result.return_value(), Err(Error::InsufficientAllowance));
+
+    // Nothing changed
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT + fee);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(borrower.account_id)).dry_run().await?;
+    assert_eq!(balance.return_value(), fee);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+    let minted = client.call(&ink_e2e::alice(), &token.flash_minted()).dry_run().await?;
+    assert_eq!(minted.return_value(), 0);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn flash_borrow_native_is_repaid_with_fee<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    // The borrower is funded with the fee in native LUNES
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    assert!(fee > 0);
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Full);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .value(fee)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    let reserve = client.free_balance(wlunes.account_id).await?;
+    let loan = token.flash_borrow_native(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &loan).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    // The reserve is back with the fee, which was minted to the fee recipient
+    assert_eq!(client.free_balance(wlunes.account_id).await?, reserve + fee);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), fee);
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT + fee);
+
+    // Every WLUNES is still backed: both holders can unwrap everything
+    let withdraw = client.call(&ink_e2e::alice(), &token.withdraw(AMOUNT)).submit().await?;
+    assert_eq!(withdraw.return_value(), Ok(()));
+    let withdraw = client.call(&ink_e2e::bob(), &token.withdraw(fee)).submit().await?;
+    assert_eq!(withdraw.return_value(), Ok(()));
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(alice)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn flash_borrow_native_short_repayment_reverts<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Short);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .value(fee)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    // The borrower sends back one unit less than the loan plus the fee
+    let reserve = client.free_balance(wlunes.account_id).await?;
+    let loan = token.flash_borrow_native(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &loan).submit().await?;
+    assert_eq!(result.return_value(), Err(Error::InsufficientBalance));
+
+    // Nothing changed
+    assert_eq!(client.free_balance(wlunes.account_id).await?, reserve);
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn guard_blocks_reentry_during_native_flash_borrow<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Reenter);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .value(fee)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    // The borrower calls `deposit` from its hook, ignores the result and
+    // repays in full. Had the call reached WLUNES, it would have returned
+    // `ReentrancyDetected` and the loan would have gone through. The runtime
+    // rejects the reentrant call instead, which traps the borrower.
+    let reserve = client.free_balance(wlunes.account_id).await?;
+    let loan = token.flash_borrow_native(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &loan).submit().await?;
+    assert_eq!(result.return_value(), Err(Error::FlashLoanFailed));
+
+    // Nothing was wrapped and the reserve is intact
+    assert_eq!(client.free_balance(wlunes.account_id).await?, reserve);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(borrower.account_id)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn deposit_call_target_pulls_exactly_the_deposit<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = RouterRef::new(wlunes.account_id);
+    let router = client
+        .instantiate("router", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("router instantiate failed");
+    let pull = ink::selector_bytes!("pull");
+
+    // The router forwards the whole deposit to Bob
+    let data = (alice, bob, AMOUNT).encode();
+    let call = token.deposit_and_call(router.account_id, pull, data);
+    let result = client.call(&ink_e2e::alice(), &call).value(AMOUNT).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), AMOUNT);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(alice)).dry_run().await?;
+    assert_eq!(balance.return_value(), 0);
+    let allowance = client.call(&ink_e2e::alice(), &token.allowance(alice, router.account_id)).dry_run().await?;
+    assert_eq!(allowance.return_value(), 0);
+
+    // One unit more than the deposit is refused and the whole call reverts.
+    // It is only dry-run: submitted with the dry run's gas limit, the nested
+    // `transfer_from` fails the gas check of a successful transfer, and the
+    // alert it emits runs out of gas before the revert
+    let data = (alice, bob, AMOUNT + 1).encode();
+    let call = token.deposit_and_call(router.account_id, pull, data);
+    let result = client.call(&ink_e2e::alice(), &call).value(AMOUNT).dry_run().await?;
+    assert_eq!(result.return_value(), Err(Error::CallFailed));
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn deposit_call_leftover_approval_is_revoked<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let bob = account_id(AccountKeyring::Bob);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = RouterRef::new(wlunes.account_id);
+    let router = client
+        .instantiate("router", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("router instantiate failed");
+
+    // An approval given before the call survives it
+    let approve = client.call(&ink_e2e::alice(), &token.approve(router.account_id, 100)).submit().await?;
+    assert_eq!(approve.return_value(), Ok(()));
+
+    // The router only spends part of the deposit
+    let data = (alice, bob, AMOUNT / 4).encode();
+    let call = token.deposit_and_call(router.account_id, ink::selector_bytes!("pull"), data);
+    let result = client.call(&ink_e2e::alice(), &call).value(AMOUNT).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    // The rest of the deposit stays with Alice, and its approval is revoked
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(alice)).dry_run().await?;
+    assert_eq!(balance.return_value(), AMOUNT - AMOUNT / 4);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), AMOUNT / 4);
+    let allowance = client.call(&ink_e2e::alice(), &token.allowance(alice, router.account_id)).dry_run().await?;
+    assert_eq!(allowance.return_value(), 100);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn withdraw_and_call_forwards_native_value<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = RouterRef::new(wlunes.account_id);
+    let router = client
+        .instantiate("router", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("router instantiate failed");
+    let router_calls = router.call_builder::<Router>();
+
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+
+    // Half of Alice's WLUNES is unwrapped straight into the router
+    let router_balance = client.free_balance(router.account_id).await?;
+    let call = token.withdraw_and_call(AMOUNT / 2, router.account_id, ink::selector_bytes!("receive"), Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).submit().await?;
+    assert_eq!(result.return_value(), Ok(()));
+
+    let received = client.call(&ink_e2e::alice(), &router_calls.received()).dry_run().await?;
+    assert_eq!(received.return_value(), AMOUNT / 2);
+    assert_eq!(client.free_balance(router.account_id).await?, router_balance + AMOUNT / 2);
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(alice)).dry_run().await?;
+    assert_eq!(balance.return_value(), AMOUNT / 2);
+    let supply = client.call(&ink_e2e::alice(), &token.total_supply()).dry_run().await?;
+    assert_eq!(supply.return_value(), AMOUNT / 2);
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn router_is_not_rate_limited<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let bob = account_id(AccountKeyring::Bob);
+    let config = SecurityConfig {
+        rate_limit_burst: 1,
+        ..e2e_config()
+    };
+    let mut constructor = WlunesRef::new_with_config(Hash::default(), 0, alice, config);
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = RouterRef::new(wlunes.account_id);
+    let router = client
+        .instantiate("router", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("router instantiate failed");
+    let mut router_calls = router.call_builder::<Router>();
+
+    // Alice spends her only token on the deposit
+    let deposit = client.call(&ink_e2e::alice(), &token.deposit()).value(AMOUNT).submit().await?;
+    assert_eq!(deposit.return_value(), Ok(()));
+    let approve = client.call(&ink_e2e::alice(), &token.approve(router.account_id, AMOUNT)).submit().await?;
+    assert_eq!(approve.return_value(), Ok(()));
+
+    // The router is a contract caller and is never charged
+    for _ in 0..5 {
+        let pull = client.call(&ink_e2e::alice(), &router_calls.pull(alice, bob, 100)).submit().await?;
+        assert_eq!(pull.return_value(), Ok(()));
+    }
+    let balance = client.call(&ink_e2e::alice(), &token.balance_of(bob)).dry_run().await?;
+    assert_eq!(balance.return_value(), 500);
+
+    // Alice calling WLUNES directly is still limited
+    let transfer = client.call(&ink_e2e::alice(), &token.transfer(bob, 1, Vec::new())).submit().await?;
+    assert_eq!(transfer.return_value(), Err(PSP22Error::Custom("OperationBlocked".into())));
+
+    Ok(())
+}
+
+#[ink_e2e::test(backend(runtime_only))]
+async fn recommended_weights_cover_dry_runs<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+    let alice = account_id(AccountKeyring::Alice);
+    let bob = account_id(AccountKeyring::Bob);
+    let (secret, signer) = ecdsa_signer(9);
+    let mut constructor = WlunesRef::new_with_config(Hash::from([0x42; 32]), FEE_BPS, bob, e2e_config());
+    let wlunes = client
+        .instantiate("wlunes", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("wlunes instantiate failed");
+    let mut token = wlunes.call_builder::<Wlunes>();
+
+    let mut constructor = Psp22ReceiverRef::new(wlunes.account_id);
+    let receiver = client
+        .instantiate("psp22_receiver", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("receiver instantiate failed");
+    let mut constructor = RouterRef::new(wlunes.account_id);
+    let router = client
+        .instantiate("router", &ink_e2e::alice(), &mut constructor)
+        .submit()
+        .await
+        .expect("router instantiate failed");
+    let fee = client.call(&ink_e2e::alice(), &token.flash_fee(LOAN)).dry_run().await?.return_value();
+    let mut constructor = FlashBorrowerRef::new(wlunes.account_id, Repayment::Full);
+    let borrower = client
+        .instantiate("flash_borrower", &ink_e2e::alice(), &mut constructor)
+        .value(fee)
+        .submit()
+        .await
+        .expect("borrower instantiate failed");
+
+    // Wraps, the first ones creating the holder's balance
+    let call = token.deposit();
+    let result = client.call(&ink_e2e::alice(), &call).value(4 * AMOUNT).dry_run().await?;
+    assert_covered(Operation::Deposit, &result);
+    client.call(&ink_e2e::alice(), &call).value(4 * AMOUNT).submit().await?;
+    let call = token.deposit_for(borrower.account_id, fee);
+    let result = client.call(&ink_e2e::alice(), &call).value(fee).dry_run().await?;
+    assert_covered(Operation::DepositFor, &result);
+    client.call(&ink_e2e::alice(), &call).value(fee).submit().await?;
+
+    // Unwraps, to existing and to new accounts
+    let call = token.set_withdrawal_fallback(true);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::SetWithdrawalFallback, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.withdraw(AMOUNT / 10);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Withdraw, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.withdraw_to(AccountId::from([0xF1; 32]), AMOUNT / 10);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::WithdrawTo, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+
+    // Allowances, then spending them from Bob
+    let call = token.approve(bob, AMOUNT);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Approve, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.increase_allowance(bob, AMOUNT);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::IncreaseAllowance, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.decrease_allowance(bob, 1);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::DecreaseAllowance, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.withdraw_from(alice, AccountId::from([0xF2; 32]), AMOUNT / 10);
+    let result = client.call(&ink_e2e::bob(), &call).dry_run().await?;
+    assert_covered(Operation::WithdrawFrom, &result);
+    client.call(&ink_e2e::bob(), &call).submit().await?;
+    let call = token.transfer_from(alice, AccountId::from([0xF3; 32]), 1_000, Vec::new());
+    let result = client.call(&ink_e2e::bob(), &call).dry_run().await?;
+    assert_covered(Operation::TransferFrom, &result);
+    client.call(&ink_e2e::bob(), &call).submit().await?;
+    let call = token.transfer_from(alice, receiver.account_id, 1_000, Vec::new());
+    let result = client.call(&ink_e2e::bob(), &call).dry_run().await?;
+    assert_covered(Operation::TransferFrom, &result);
+    client.call(&ink_e2e::bob(), &call).submit().await?;
+
+    // Signed messages, verified with ECDSA
+    let call = token.deposit_for(signer, AMOUNT);
+    client.call(&ink_e2e::alice(), &call).value(AMOUNT).submit().await?;
+    let domain_separator = client.call(&ink_e2e::alice(), &token.domain_separator()).dry_run().await?.return_value();
+    let signature = sign_ecdsa(&secret, &(domain_separator, signer, bob, AMOUNT, 0u64, u64::MAX));
+    let call = token.permit(signer, bob, AMOUNT, u64::MAX, signature);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Permit, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let intent = SignedIntent {
+        owner: signer,
+        action: SignedAction::Withdraw { recipient: AccountId::from([0xF5; 32]), amount: AMOUNT / 10 },
+        nonce: 1,
+        deadline: u64::MAX,
+        max_fee: 1_000,
+    };
+    let signature = sign_ecdsa(&secret, &(domain_separator, b"WLUNES::execute_signed".as_slice(), &intent));
+    let call = token.execute_signed(intent, 1_000, signature);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::ExecuteSigned, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+
+    // Transfers to a new account and to a contract with a receiver hook
+    let call = token.transfer(AccountId::from([0xF4; 32]), 1_000, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Transfer, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.transfer(receiver.account_id, 1_000, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Transfer, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+
+    // Batches of one and ten entries to the receiver contract, which cost
+    // the most per entry
+    for entries in [1u8, 10] {
+        let recipients: Vec<(AccountId, Balance)> = (0..entries).map(|_| (receiver.account_id, 1_000)).collect();
+        let call = token.batch_transfer(recipients.clone(), Vec::new());
+        let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+        assert_covered(Operation::BatchTransfer { recipients: entries.into() }, &result);
+        client.call(&ink_e2e::alice(), &call).submit().await?;
+        let call = token.batch_transfer_from(alice, recipients, Vec::new());
+        let result = client.call(&ink_e2e::bob(), &call).dry_run().await?;
+        assert_covered(Operation::BatchTransferFrom { recipients: entries.into() }, &result);
+        client.call(&ink_e2e::bob(), &call).submit().await?;
+        let calls: Vec<Call> = (0..entries)
+            .map(|_| Call::Transfer { to: receiver.account_id, value: 1_000, data: Vec::new() })
+            .collect();
+        let call = token.multicall(calls);
+        let result = client.call(&ink_e2e::alice(), &call).value(AMOUNT).dry_run().await?;
+        assert_covered(Operation::Multicall { calls: entries.into() }, &result);
+        client.call(&ink_e2e::alice(), &call).value(AMOUNT).submit().await?;
+    }
+
+    // Burning leaves a surplus for skim
+    let call = token.burn(alice, 1_000);
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Burn, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.skim();
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::Skim, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+
+    // Calls into other contracts, with the callee's minimal work included
+    let call = token.deposit_and_call(router.account_id, ink::selector_bytes!("pull"), (alice, bob, AMOUNT).encode());
+    let result = client.call(&ink_e2e::alice(), &call).value(AMOUNT).dry_run().await?;
+    assert_covered(Operation::DepositAndCall, &result);
+    client.call(&ink_e2e::alice(), &call).value(AMOUNT).submit().await?;
+    let call = token.withdraw_and_call(AMOUNT / 10, router.account_id, ink::selector_bytes!("receive"), Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::WithdrawAndCall, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.flash_loan(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::FlashLoan, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+    let call = token.flash_borrow_native(borrower.account_id, LOAN, Vec::new());
+    let result = client.call(&ink_e2e::alice(), &call).dry_run().await?;
+    assert_covered(Operation::FlashBorrowNative, &result);
+    client.call(&ink_e2e::alice(), &call).submit().await?;
+
+    Ok(())
+}
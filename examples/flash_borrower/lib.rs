@@ -0,0 +1,122 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+// WLUNES builds this example for its end-to-end tests, so the example cannot
+// depend on the `wlunes` crate in turn. It compiles the WLUNES trait
+// definitions from source instead; other contracts depend on `wlunes` with
+// the `ink-as-dependency` feature.
+#[path = "../../traits.rs"]
+pub mod traits;
+
+/// Reference flash loan borrower.
+///
+/// Implements both WLUNES flash loan callbacks. A borrower normally runs its
+/// strategy (an arbitrage, a liquidation) inside the callback and then repays;
+/// this one only repays, which is enough to show the repayment rules. The
+/// repayment mode is fixed at deployment so the failure paths can be
+/// exercised as well.
+#[ink::contract]
+pub mod flash_borrower {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use ink::prelude::vec::Vec;
+    use crate::traits::{FlashBorrowerError, NativeFlashBorrower, PSP22FlashBorrower, PSP22};
+
+    /// How the borrower repays a loan.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Repayment {
+        /// Repays `amount + fee`
+        Full,
+        /// Repays one unit less than `amount + fee`
+        Short,
+        /// Tries to wrap the borrowed LUNES, ignores the outcome, then repays
+        /// in full
+        Reenter,
+    }
+
+    #[ink(storage)]
+    pub struct FlashBorrower {
+        /// WLUNES contract this borrower takes loans from
+        token: AccountId,
+        /// Repayment behaviour
+        repayment: Repayment,
+    }
+
+    impl FlashBorrower {
+        /// Creates a borrower for `token`. Attach native LUNES to pay the fees
+        /// of native loans.
+        #[ink(constructor, payable)]
+        pub fn new(token: AccountId, repayment: Repayment) -> Self {
+            Self { token, repayment }
+        }
+
+        /// Amount to hand back for a loan of `amount` with `fee`.
+        fn repayment_due(&self, amount: Balance, fee: Balance) -> Result<Balance, FlashBorrowerError> {
+            let due = amount
+                .checked_add(fee)
+                .ok_or_else(|| FlashBorrowerError::FlashloanRejected("Overflow".into()))?;
+            match self.repayment {
+                Repayment::Short => Ok(due.saturating_sub(1)),
+                Repayment::Full | Repayment::Reenter => Ok(due),
+            }
+        }
+
+        /// Only loans from the configured token are accepted.
+        fn ensure_token(&self) -> Result<(), FlashBorrowerError> {
+            if self.env().caller() != self.token {
+                return Err(FlashBorrowerError::FlashloanRejected("Unsupported token".into()));
+            }
+            Ok(())
+        }
+    }
+
+    impl PSP22FlashBorrower for FlashBorrower {
+        /// Approves WLUNES to pull the repayment.
+        #[ink(message)]
+        fn on_flash_loan(
+            &mut self,
+            _initiator: AccountId,
+            amount: Balance,
+            fee: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), FlashBorrowerError> {
+            self.ensure_token()?;
+            let due = self.repayment_due(amount, fee)?;
+
+            let mut token: ink::contract_ref!(PSP22) = self.token.into();
+            token
+                .approve(self.token, due)
+                .map_err(|_| FlashBorrowerError::FlashloanRejected("Approve failed".into()))
+        }
+    }
+
+    impl NativeFlashBorrower for FlashBorrower {
+        /// Sends the borrowed LUNES plus the fee back to WLUNES.
+        #[ink(message, payable)]
+        fn on_flash_borrow(
+            &mut self,
+            _initiator: AccountId,
+            amount: Balance,
+            fee: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), FlashBorrowerError> {
+            self.ensure_token()?;
+            let due = self.repayment_due(amount, fee)?;
+
+            if self.repayment == Repayment::Reenter {
+                // The outcome is ignored, so the return value is not decoded
+                let _ = build_call::<DefaultEnvironment>()
+                    .call(self.token)
+                    .transferred_value(amount)
+                    .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!("deposit"))))
+                    .returns::<()>()
+                    .try_invoke();
+            }
+
+            self.env()
+                .transfer(self.token, due)
+                .map_err(|_| FlashBorrowerError::FlashloanRejected("Repayment failed".into()))
+        }
+    }
+}
@@ -0,0 +1,106 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+// WLUNES builds this example for its end-to-end tests, so the example cannot
+// depend on the `wlunes` crate in turn. It compiles the WLUNES trait
+// definitions from source instead; other contracts depend on `wlunes` with
+// the `ink-as-dependency` feature.
+#[path = "../../traits.rs"]
+pub mod traits;
+
+/// Reference `PSP22Receiver` implementation.
+///
+/// WLUNES calls `before_received` whenever tokens are transferred to a
+/// contract. This receiver only accepts WLUNES from the token contract it
+/// was deployed with and keeps track of how much each sender deposited.
+#[ink::contract]
+pub mod psp22_receiver {
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use crate::traits::{PSP22Receiver, PSP22ReceiverError};
+
+    #[ink(storage)]
+    pub struct Psp22Receiver {
+        /// WLUNES contract this receiver accepts tokens from
+        token: AccountId,
+        /// Amount received per sender
+        received: Mapping<AccountId, Balance>,
+    }
+
+    /// Event emitted when a transfer is accepted.
+    #[ink(event)]
+    pub struct Received {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    impl Psp22Receiver {
+        /// Creates a receiver that accepts transfers from `token`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token,
+                received: Mapping::default(),
+            }
+        }
+
+        /// Returns the amount received from `from`.
+        #[ink(message)]
+        pub fn received_from(&self, from: AccountId) -> Balance {
+            self.received.get(from).unwrap_or(0)
+        }
+    }
+
+    impl PSP22Receiver for Psp22Receiver {
+        /// Accepts transfers from the configured WLUNES contract only.
+        #[ink(message)]
+        fn before_received(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22ReceiverError> {
+            if self.env().caller() != self.token {
+                return Err(PSP22ReceiverError::TransferRejected(
+                    "Unsupported token".into(),
+                ));
+            }
+
+            let total = self
+                .received_from(from)
+                .checked_add(value)
+                .ok_or_else(|| PSP22ReceiverError::TransferRejected("Overflow".into()))?;
+            self.received.insert(from, &total);
+
+            self.env().emit_event(Received {
+                operator,
+                from,
+                value,
+            });
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        #[ink::test]
+        fn accepts_only_configured_token() {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            let mut receiver = Psp22Receiver::new(accounts.django);
+
+            test::set_caller::<DefaultEnvironment>(accounts.django);
+            assert_eq!(receiver.before_received(accounts.alice, accounts.alice, 10, Vec::new()), Ok(()));
+            assert_eq!(receiver.received_from(accounts.alice), 10);
+
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(receiver.before_received(accounts.alice, accounts.alice, 10, Vec::new()).is_err());
+        }
+    }
+}
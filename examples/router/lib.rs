@@ -0,0 +1,54 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+// WLUNES builds this example for its end-to-end tests, so the example cannot
+// depend on the `wlunes` crate in turn. It compiles the WLUNES trait
+// definitions from source instead; other contracts depend on `wlunes` with
+// the `ink-as-dependency` feature.
+#[path = "../../traits.rs"]
+pub mod traits;
+
+/// Reference router called through `deposit_and_call` and `withdraw_and_call`.
+///
+/// `pull` is the shape of a `deposit_and_call` target: WLUNES approves the
+/// router for the deposit and the router spends that approval with
+/// `transfer_from`. `receive` is a payable `withdraw_and_call` target that
+/// keeps track of the native LUNES it was sent.
+#[ink::contract]
+pub mod router {
+    use crate::traits::{PSP22Error, PSP22};
+
+    #[ink(storage)]
+    pub struct Router {
+        /// WLUNES contract the router works with
+        token: AccountId,
+        /// Native LUNES received through `receive`
+        received: Balance,
+    }
+
+    impl Router {
+        /// Creates a router for `token`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self { token, received: 0 }
+        }
+
+        /// Moves `value` WLUNES from `from` to `to` using the router's allowance.
+        #[ink(message)]
+        pub fn pull(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let mut token: ink::contract_ref!(PSP22) = self.token.into();
+            token.transfer_from(from, to, value, Default::default())
+        }
+
+        /// Accepts native LUNES.
+        #[ink(message, payable)]
+        pub fn receive(&mut self) {
+            self.received = self.received.saturating_add(self.env().transferred_value());
+        }
+
+        /// Returns the native LUNES received through `receive`.
+        #[ink(message)]
+        pub fn received(&self) -> Balance {
+            self.received
+        }
+    }
+}
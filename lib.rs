@@ -1,7 +1,22 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub mod traits;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(all(test, feature = "e2e-tests"))]
+mod e2e_tests;
+
 #[ink::contract]
 pub mod wlunes {
+    use crate::traits::{
+        FlashBorrowerError, PSP22, PSP22Burnable, PSP22Error, PSP22Metadata, PSP22ReceiverError,
+    };
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::CallFlags;
+    use ink::env::hash::Blake2x256;
+    use ink::env::DefaultEnvironment;
     use ink::storage::Mapping;
     use ink::prelude::vec::Vec;
     use ink::prelude::string::String;
@@ -20,7 +35,8 @@ pub mod wlunes {
     /// - 1:1 invariant protection between WLUNES and native LUNES
     #[ink(storage)]
     pub struct Wlunes {
-        /// Total token supply - must always equal contract's native balance (1:1 invariant)
+        /// Total token supply - always backed by the contract's native balance
+        /// Any native balance above it is surplus that `skim` pays out
         total_supply: Balance,
         
         /// Optimized mapping from owner to token balance
@@ -35,35 +51,68 @@ pub mod wlunes {
         /// Critical security feature for all state-changing operations
         reentrancy_guard: bool,
         
-        /// Maximum gas limit threshold for DoS protection
-        /// Configurable limit to prevent resource exhaustion attacks
-        max_gas_limit: u64,
-        
-        /// Contract deployment timestamp for temporal validations
-        /// Used for rate limiting and audit trail functionality
-        deployment_timestamp: u64,
+        /// Security limits and enforcement modes, fixed at construction
+        /// Readable through `security_config`
+        config: SecurityConfig,
         
         /// Rate limiting: tracks last transaction timestamp per account
         /// Prevents spam attacks and suspicious rapid transactions
         last_transaction: Mapping<AccountId, u64>,
         
-        /// Rate limiting: minimum time between transactions (in milliseconds)
-        /// Configurable cooldown period for enhanced security
-        transaction_cooldown: u64,
+        /// Rate limiting: per-account token bucket as (tokens left, last refill time)
+        /// Refills `rate_limit_burst` tokens per `rate_limit_window`
+        rate_limits: Mapping<AccountId, (u32, u64)>,
+        
+        /// Chain identifier (genesis hash) included in signed payloads
+        /// Prevents replaying permits on another chain
+        chain_id: Hash,
+        
+        /// Per-owner nonce for signed approvals
+        /// Incremented on every accepted signature to prevent replays
+        nonces: Mapping<AccountId, u64>,
+        
+        /// Flash loan fee in basis points, fixed at construction
+        flash_fee_bps: u16,
+        
+        /// Account receiving flash loan fees and skimmed surplus, fixed at construction
+        fee_recipient: AccountId,
         
-        /// Transaction counter for each account - detects suspicious patterns
-        /// Used for advanced security analytics and pattern recognition
-        transaction_count: Mapping<AccountId, u32>,
+        /// Set while a flash loan callback is running
+        /// Blocks wrapping, unwrapping and nested flash loans during the loan
+        flash_loan_active: bool,
+        
+        /// WLUNES flash-minted by the running flash loan, not yet backed
+        /// Exempt from the backing invariant until the loan is repaid
+        flash_minted: Balance,
+        
+        /// Set to (caller, target) while `deposit_and_call` runs its target
+        /// The guard stays engaged; only the target's `transfer_from` of the caller's WLUNES may re-enter
+        deposit_call: Option<(AccountId, AccountId)>,
+        
+        /// Set while a message that already charged the rate limiter runs
+        /// (a multicall batch, a signed intent, a transfer to this contract), so nested
+        /// wraps and unwraps are not charged again
+        rate_limit_charged: bool,
+        
+        /// Native LUNES owed to owners whose withdrawal could not be pushed
+        /// Collected with `claim_withdrawal`
+        pending_withdrawals: Mapping<AccountId, Balance>,
+        
+        /// Sum of all pending withdrawals, excluded from the backing balance
+        total_pending: Balance,
+        
+        /// Owners that opted into recording failed withdrawals as pending claims
+        withdrawal_fallback: Mapping<AccountId, bool>,
     }
 
     /// Event emitted when a token transfer occurs.
     #[ink(event)]
     pub struct Transfer {
         #[ink(topic)]
-        from: Option<AccountId>,
+        pub from: Option<AccountId>,
         #[ink(topic)]
-        to: Option<AccountId>,
-        value: Balance,
+        pub to: Option<AccountId>,
+        pub value: Balance,
     }
 
     /// Event emitted when an approval occurs that `spender` is allowed 
@@ -71,43 +120,115 @@ pub mod wlunes {
     #[ink(event)]
     pub struct Approval {
         #[ink(topic)]
-        owner: AccountId,
+        pub owner: AccountId,
         #[ink(topic)]
-        spender: AccountId,
-        value: Balance,
+        pub spender: AccountId,
+        pub value: Balance,
     }
 
     /// Event emitted when a deposit occurs.
+    /// `payer` supplied the native LUNES, `account` received the minted WLUNES.
     #[ink(event)]
     pub struct Deposit {
         #[ink(topic)]
-        account: AccountId,
-        amount: Balance,
+        pub payer: AccountId,
         #[ink(topic)]
-        timestamp: u64,
+        pub account: AccountId,
+        pub amount: Balance,
+        #[ink(topic)]
+        pub timestamp: u64,
     }
 
     /// Event emitted when a withdrawal occurs.
+    /// `account` had its WLUNES burned, `recipient` received the native LUNES.
     #[ink(event)]
     pub struct Withdrawal {
         #[ink(topic)]
-        account: AccountId,
-        amount: Balance,
+        pub account: AccountId,
+        #[ink(topic)]
+        pub recipient: AccountId,
+        pub amount: Balance,
+        #[ink(topic)]
+        pub timestamp: u64,
+    }
+
+    /// Event emitted when a failed withdrawal is recorded as a pending claim.
+    #[ink(event)]
+    pub struct WithdrawalDeferred {
+        #[ink(topic)]
+        pub account: AccountId,
+        #[ink(topic)]
+        pub recipient: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Event emitted when a pending withdrawal is claimed.
+    #[ink(event)]
+    pub struct WithdrawalClaimed {
+        #[ink(topic)]
+        pub account: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Event emitted when a relayer executes an owner-signed intent.
+    #[ink(event)]
+    pub struct SignedIntentExecuted {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub relayer: AccountId,
+        pub nonce: u64,
+        pub fee: Balance,
+    }
+
+    /// Event emitted when a flash loan is repaid.
+    #[ink(event)]
+    pub struct FlashLoan {
+        #[ink(topic)]
+        pub receiver: AccountId,
+        #[ink(topic)]
+        pub initiator: AccountId,
+        pub amount: Balance,
+        pub fee: Balance,
+    }
+
+    /// Event emitted when a native LUNES flash loan is repaid.
+    #[ink(event)]
+    pub struct NativeFlashLoan {
+        #[ink(topic)]
+        pub receiver: AccountId,
+        #[ink(topic)]
+        pub initiator: AccountId,
+        pub amount: Balance,
+        pub fee: Balance,
+    }
+
+    /// Event emitted when surplus native LUNES is skimmed.
+    #[ink(event)]
+    pub struct Skim {
+        #[ink(topic)]
+        pub caller: AccountId,
         #[ink(topic)]
-        timestamp: u64,
+        pub beneficiary: AccountId,
+        pub amount: Balance,
     }
 
     /// Event emitted when a security violation is detected.
     #[ink(event)]
     pub struct SecurityAlert {
         #[ink(topic)]
-        alert_type: u8, // 1: Reentrancy, 2: Gas limit, 3: Suspicious activity
+        pub alert_type: u8, // 1: Reentrancy, 2: Gas limit, 3: Suspicious activity
         #[ink(topic)]
-        account: AccountId,
-        details: Balance,
+        pub account: AccountId,
+        pub details: Balance,
     }
 
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    /// WLUNES error type returned by the wrap/unwrap messages.
+    ///
+    /// PSP22 messages return [`PSP22Error`] instead; variants without a PSP22
+    /// counterpart are reported as `PSP22Error::Custom(code)` where `code` is
+    /// the stable string returned by [`Error::code`].
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum Error {
         /// Returned if not enough balance to fulfill a request is available.
@@ -130,6 +251,395 @@ pub mod wlunes {
         AmountTooLarge,
         /// Returned if operation is temporarily blocked.
         OperationBlocked,
+        /// Returned if the declared amount differs from the transferred value.
+        AmountMismatch,
+        /// Returned if a contract recipient rejected the transfer or lacks the
+        /// `PSP22Receiver` hook, with the receiver's reason.
+        SafeTransferCheckFailed(String),
+        /// Returned if a signed payload is past its deadline.
+        SignatureExpired,
+        /// Returned if a signature does not match the signed payload.
+        InvalidSignature,
+        /// Returned if a signed payload does not use the owner's next nonce.
+        InvalidNonce,
+        /// Returned if the relayer fee exceeds the signed maximum.
+        FeeTooHigh,
+        /// Returned if a flash loan receiver is not a contract or its
+        /// callback failed.
+        FlashLoanFailed,
+        /// Returned if a batch exceeds `MAX_BATCH_SIZE` entries.
+        BatchTooLarge,
+        /// Returned if the target of `deposit_and_call` or `withdraw_and_call`
+        /// reverted or returned an error.
+        CallFailed,
+        /// Returned if the contract's native balance, net of its existential
+        /// deposit and pending claims, cannot cover a withdrawal.
+        InsufficientReserve,
+        /// Returned if a native transfer of less than the existential deposit
+        /// failed. The usual cause is a recipient account that does not exist
+        /// yet, which such a transfer cannot create; WLUNES cannot read other
+        /// accounts' balances, so this is not checked before the transfer.
+        NativeTransferBelowExistentialDeposit,
+        /// Returned if a native transfer failed for any other reason.
+        NativeTransferFailed,
+        /// Returned by signed messages if the contract was deployed without a
+        /// chain id, so signatures would not be bound to one chain.
+        ChainIdNotSet,
+    }
+
+    impl Error {
+        /// Stable string code used for `PSP22Error::Custom`.
+        pub fn code(&self) -> &'static str {
+            match self {
+                Error::InsufficientBalance => "InsufficientBalance",
+                Error::InsufficientAllowance => "InsufficientAllowance",
+                Error::Overflow => "Overflow",
+                Error::ZeroAmount => "ZeroAmount",
+                Error::ZeroAddress => "ZeroAddress",
+                Error::InvalidState => "InvalidState",
+                Error::ReentrancyDetected => "ReentrancyDetected",
+                Error::GasLimitExceeded => "GasLimitExceeded",
+                Error::AmountTooLarge => "AmountTooLarge",
+                Error::OperationBlocked => "OperationBlocked",
+                Error::AmountMismatch => "AmountMismatch",
+                Error::SafeTransferCheckFailed(_) => "SafeTransferCheckFailed",
+                Error::SignatureExpired => "SignatureExpired",
+                Error::InvalidSignature => "InvalidSignature",
+                Error::InvalidNonce => "InvalidNonce",
+                Error::FeeTooHigh => "FeeTooHigh",
+                Error::FlashLoanFailed => "FlashLoanFailed",
+                Error::BatchTooLarge => "BatchTooLarge",
+                Error::CallFailed => "CallFailed",
+                Error::InsufficientReserve => "InsufficientReserve",
+                Error::NativeTransferBelowExistentialDeposit => "NativeTransferBelowExistentialDeposit",
+                Error::NativeTransferFailed => "NativeTransferFailed",
+                Error::ChainIdNotSet => "ChainIdNotSet",
+            }
+        }
+
+        /// Converts into a `PSP22Error`, reporting a zero address as `zero_address`.
+        fn into_psp22(self, zero_address: PSP22Error) -> PSP22Error {
+            match self {
+                Error::ZeroAddress => zero_address,
+                other => other.into(),
+            }
+        }
+    }
+
+    /// Signature over a WLUNES signed payload.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Signature {
+        /// sr25519 signature by the owner's public key (the `AccountId`).
+        Sr25519([u8; 64]),
+        /// Recoverable ECDSA signature; the owner's `AccountId` is the
+        /// blake2-256 hash of the compressed public key.
+        Ecdsa([u8; 65]),
+    }
+
+    /// SCALE-encoded approval payload signed for `permit`.
+    #[derive(scale::Encode)]
+    struct PermitPayload {
+        domain_separator: [u8; 32],
+        owner: AccountId,
+        spender: AccountId,
+        value: Balance,
+        nonce: u64,
+        deadline: u64,
+    }
+
+    /// Domain tag mixed into the domain separator.
+    const PERMIT_DOMAIN: &[u8] = b"WLUNES::permit";
+
+    /// Maximum number of recipients in a single batch transfer.
+    pub const MAX_BATCH_SIZE: usize = 100;
+
+    /// Basis point denominator used for fees.
+    const MAX_BPS: u16 = 10_000;
+
+    /// Tag distinguishing signed intents from permit payloads.
+    const SIGNED_INTENT_TAG: &[u8] = b"WLUNES::execute_signed";
+
+    /// `SafeTransferCheckFailed` reason when a contract recipient lacks the
+    /// `PSP22Receiver` hook or its call trapped.
+    pub const RECEIVER_CALL_FAILED: &str = "PSP22Receiver::before_received failed";
+
+    /// Action an owner authorizes a relayer to execute on their behalf.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum SignedAction {
+        /// Transfer `value` WLUNES from the owner to `to`.
+        Transfer { to: AccountId, value: Balance },
+        /// Set the owner's allowance for `spender` to `value`.
+        Approve { spender: AccountId, value: Balance },
+        /// Unwrap `amount` of the owner's WLUNES to `recipient`.
+        Withdraw { recipient: AccountId, amount: Balance },
+    }
+
+    /// Owner-signed intent submitted through `execute_signed`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SignedIntent {
+        /// Account whose tokens the action uses
+        pub owner: AccountId,
+        /// Action to execute
+        pub action: SignedAction,
+        /// Must equal `nonces(owner)`
+        pub nonce: u64,
+        /// Block timestamp after which the intent expires
+        pub deadline: u64,
+        /// Maximum WLUNES fee the relayer may take from the owner
+        pub max_fee: Balance,
+    }
+
+    /// Whether a security check rejects the call or only emits `SecurityAlert`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum CheckMode {
+        /// Emit `SecurityAlert` and fail the call.
+        Block,
+        /// Emit `SecurityAlert` and let the call proceed.
+        Alert,
+    }
+
+    /// State-changing WLUNES messages, for `recommended_weight` and the gas
+    /// guard. Batches carry their number of entries.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Operation {
+        Deposit,
+        DepositFor,
+        Withdraw,
+        WithdrawTo,
+        WithdrawFrom,
+        SetWithdrawalFallback,
+        ClaimWithdrawal,
+        DepositAndCall,
+        WithdrawAndCall,
+        BatchTransfer { recipients: u32 },
+        BatchTransferFrom { recipients: u32 },
+        Multicall { calls: u32 },
+        Permit,
+        ExecuteSigned,
+        FlashLoan,
+        FlashBorrowNative,
+        Skim,
+        Transfer,
+        TransferFrom,
+        Approve,
+        IncreaseAllowance,
+        DecreaseAllowance,
+        Burn,
+    }
+
+    /// Weight v2 limit: computation time and proof size.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct OperationWeight {
+        /// Computation time in picoseconds
+        pub ref_time: u64,
+        /// Storage proof size in bytes
+        pub proof_size: u64,
+    }
+
+    impl Operation {
+        /// Recommended gas limit for the operation: its most expensive path
+        /// measured on the pallet-contracts reference weights, plus a tenth
+        /// and rounded up. That includes fresh accounts, unwraps, ECDSA
+        /// signatures and contract recipients with a no-op `PSP22Receiver`
+        /// hook. Whatever a hook, borrower or call target does beyond that is
+        /// extra.
+        pub fn recommended_weight(&self) -> OperationWeight {
+            let (ref_time, proof_size) = match *self {
+                Operation::Deposit | Operation::DepositFor => (4_500_000_000, 117_000),
+                Operation::Withdraw | Operation::WithdrawTo => (4_700_000_000, 120_000),
+                Operation::WithdrawFrom => (5_300_000_000, 128_000),
+                Operation::SetWithdrawalFallback => (3_000_000_000, 93_000),
+                Operation::ClaimWithdrawal => (3_500_000_000, 103_000),
+                Operation::DepositAndCall => (11_700_000_000, 249_000),
+                Operation::WithdrawAndCall => (6_400_000_000, 142_000),
+                Operation::BatchTransfer { recipients } => (
+                    per_entry(3_400_000_000, 2_660_000_000, recipients),
+                    per_entry(97_000, 41_200, recipients),
+                ),
+                Operation::BatchTransferFrom { recipients } => (
+                    per_entry(4_100_000_000, 2_660_000_000, recipients),
+                    per_entry(106_000, 41_200, recipients),
+                ),
+                Operation::Multicall { calls } => (
+                    per_entry(4_500_000_000, 2_860_000_000, calls),
+                    per_entry(117_000, 41_900, calls),
+                ),
+                Operation::Permit => (3_700_000_000, 102_000),
+                Operation::ExecuteSigned => (6_300_000_000, 141_000),
+                Operation::FlashLoan => (11_700_000_000, 251_000),
+                Operation::FlashBorrowNative => (6_200_000_000, 142_000),
+                Operation::Skim => (3_500_000_000, 103_000),
+                Operation::Transfer => (6_100_000_000, 138_000),
+                Operation::TransferFrom => (6_700_000_000, 147_000),
+                Operation::Approve => (3_400_000_000, 101_000),
+                Operation::IncreaseAllowance | Operation::DecreaseAllowance => (3_500_000_000, 101_000),
+                Operation::Burn => (3_500_000_000, 101_000),
+            };
+            OperationWeight { ref_time, proof_size }
+        }
+
+        /// Smallest `ref_time` left at the gas check that can complete the
+        /// operation: its cheapest successful path measured on the
+        /// pallet-contracts reference weights, less a fifth and rounded down.
+        /// Gas limits taken from a dry run are therefore never rejected.
+        pub fn min_ref_time(&self) -> u64 {
+            match *self {
+                Operation::Deposit | Operation::DepositFor => 1_340_000_000,
+                Operation::Withdraw | Operation::WithdrawTo => 1_470_000_000,
+                Operation::WithdrawFrom => 1_940_000_000,
+                Operation::SetWithdrawalFallback => 240_000_000,
+                Operation::ClaimWithdrawal => 600_000_000,
+                Operation::DepositAndCall => 3_220_000_000,
+                Operation::WithdrawAndCall => 2_580_000_000,
+                Operation::BatchTransfer { recipients } => per_entry(420_000_000, 3_840_000, recipients),
+                Operation::BatchTransferFrom { recipients } => per_entry(890_000_000, 3_920_000, recipients),
+                Operation::Multicall { calls } => per_entry(440_000_000, 445_000_000, calls),
+                Operation::Permit => 750_000_000,
+                Operation::ExecuteSigned => 1_240_000_000,
+                Operation::FlashLoan => 5_890_000_000,
+                Operation::FlashBorrowNative => 2_510_000_000,
+                Operation::Skim => 590_000_000,
+                Operation::Transfer => 910_000_000,
+                Operation::TransferFrom => 1_380_000_000,
+                Operation::Approve => 560_000_000,
+                Operation::IncreaseAllowance | Operation::DecreaseAllowance => 600_000_000,
+                Operation::Burn => 590_000_000,
+            }
+        }
+    }
+
+    /// Weight of a batch: a fixed part plus a part per entry.
+    fn per_entry(base: u64, per_entry: u64, entries: u32) -> u64 {
+        base.saturating_add(per_entry.saturating_mul(u64::from(entries)))
+    }
+
+    /// Deploy-time security limits, stored immutably by `new_with_config`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct SecurityConfig {
+        /// Minimum time between rate-limited operations of one account, in milliseconds
+        pub transaction_cooldown: u64,
+        /// Window (in milliseconds) over which the rate limit bucket refills
+        pub rate_limit_window: u64,
+        /// Operations allowed in a burst; 0 disables the bucket
+        pub rate_limit_burst: u32,
+        /// Largest amount a single operation may move (`AmountTooLarge`)
+        pub max_transaction_amount: Balance,
+        /// Wrap/unwrap amount above which the call is flagged as suspicious
+        pub suspicious_amount: Balance,
+        /// Ceiling on the total supply, always enforced
+        pub max_total_supply: Balance,
+        /// Enforcement of the per-operation weight check
+        pub gas_limit_mode: CheckMode,
+        /// Enforcement of the cooldown and rate limit bucket
+        pub rate_limit_mode: CheckMode,
+        /// Enforcement of `max_transaction_amount`
+        pub amount_limit_mode: CheckMode,
+        /// Enforcement of `suspicious_amount`
+        pub pattern_mode: CheckMode,
+    }
+
+    impl Default for SecurityConfig {
+        fn default() -> Self {
+            Self {
+                transaction_cooldown: 1000, // 1 second cooldown between transactions
+                rate_limit_window: 3_600_000, // 1 hour
+                rate_limit_burst: 100,
+                max_transaction_amount: Balance::MAX / 10, // 10% of max supply
+                suspicious_amount: Balance::MAX / 1000, // 0.1% of max supply
+                max_total_supply: Balance::MAX / 2,
+                gas_limit_mode: CheckMode::Block,
+                rate_limit_mode: CheckMode::Block,
+                amount_limit_mode: CheckMode::Block,
+                pattern_mode: CheckMode::Block,
+            }
+        }
+    }
+
+    /// WLUNES operation executed by `multicall` with the caller as the actor.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Call {
+        /// Transfer `value` of the caller's WLUNES to `to`.
+        Transfer { to: AccountId, value: Balance, data: Vec<u8> },
+        /// Transfer `value` WLUNES from `from` to `to` using the caller's allowance.
+        TransferFrom { from: AccountId, to: AccountId, value: Balance, data: Vec<u8> },
+        /// Set the caller's allowance for `spender` to `value`.
+        Approve { spender: AccountId, value: Balance },
+        /// Unwrap `amount` of the caller's WLUNES to `recipient`.
+        Withdraw { recipient: AccountId, amount: Balance },
+    }
+
+    /// Error returned by `multicall`; the whole batch is reverted.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct MulticallError {
+        /// Results of the calls that ran, in order, ending with the failing
+        /// call. Empty if the batch itself was rejected (size limit, rate
+        /// limiting, deposit of the attached value)
+        pub results: Vec<Result<(), Error>>,
+        /// Error returned by the failing step
+        pub error: Error,
+    }
+
+    impl From<Error> for MulticallError {
+        fn from(error: Error) -> Self {
+            MulticallError { results: Vec::new(), error }
+        }
+    }
+
+    /// Calldata appended verbatim after the selector of a forwarded call.
+    struct RawCallData(Vec<u8>);
+
+    impl scale::Encode for RawCallData {
+        fn size_hint(&self) -> usize {
+            self.0.len()
+        }
+
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(&self.0);
+        }
+    }
+
+    /// Undecoded return value of a forwarded call.
+    struct RawCallOutput(Vec<u8>);
+
+    impl RawCallOutput {
+        /// A target returning `()` or `Ok(..)` succeeded; `Err(..)` did not.
+        fn succeeded(&self) -> bool {
+            matches!(self.0.first(), None | Some(0))
+        }
+    }
+
+    impl scale::Decode for RawCallOutput {
+        fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let mut output = ink::prelude::vec![0; input.remaining_len()?.unwrap_or(0)];
+            input.read(&mut output)?;
+            Ok(RawCallOutput(output))
+        }
+    }
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance => PSP22Error::InsufficientAllowance,
+                Error::SafeTransferCheckFailed(reason) => PSP22Error::SafeTransferCheckFailed(reason),
+                other => PSP22Error::Custom(other.code().into()),
+            }
+        }
     }
 
     impl Default for Wlunes {
@@ -144,22 +654,65 @@ pub mod wlunes {
         /// Initializes all security systems:
         /// - Reentrancy protection
         /// - Rate limiting (1 second cooldown)
-        /// - Gas limit validation (1M gas threshold)
+        /// - Weight-aware gas validation per operation
         /// - Transaction pattern monitoring
         /// 
         /// Returns a fully configured, production-ready WLUNES contract.
+        /// It has no chain id, so `permit` and `execute_signed` are disabled;
+        /// deploy with `new_for_chain` to enable them.
         #[ink(constructor)]
         pub fn new() -> Self {
+            Self::new_for_chain(Hash::default())
+        }
+
+        /// Creates a new WLUNES contract bound to `chain_id` (the genesis hash
+        /// of the target chain), which is committed to by every permit signature.
+        #[ink(constructor)]
+        pub fn new_for_chain(chain_id: Hash) -> Self {
+            Self::new_with_flash_fee(chain_id, 0, Self::env().caller())
+        }
+
+        /// Creates a new WLUNES contract bound to `chain_id` that charges
+        /// `flash_fee_bps` basis points on flash loans, paid to `fee_recipient`.
+        ///
+        /// The fee is capped at 100% (10_000 basis points).
+        #[ink(constructor)]
+        pub fn new_with_flash_fee(chain_id: Hash, flash_fee_bps: u16, fee_recipient: AccountId) -> Self {
+            Self::new_with_config(chain_id, flash_fee_bps, fee_recipient, SecurityConfig::default())
+        }
+
+        /// Creates a new WLUNES contract with deploy-time security limits.
+        ///
+        /// `config` sets cooldowns, rate limits, per-transaction caps, pattern
+        /// thresholds and whether each check blocks or only alerts, so testnet
+        /// and mainnet deployments can differ without code changes. It cannot
+        /// be changed after deployment.
+        #[ink(constructor)]
+        pub fn new_with_config(
+            chain_id: Hash,
+            flash_fee_bps: u16,
+            fee_recipient: AccountId,
+            config: SecurityConfig,
+        ) -> Self {
             Self {
                 total_supply: 0,
                 balances: Mapping::default(),
                 allowances: Mapping::default(),
                 reentrancy_guard: false,
-                max_gas_limit: 1_000_000, // 1M gas limit for DoS protection
-                deployment_timestamp: Self::env().block_timestamp(),
+                config,
                 last_transaction: Mapping::default(),
-                transaction_cooldown: 1000, // 1 second cooldown between transactions
-                transaction_count: Mapping::default(),
+                rate_limits: Mapping::default(),
+                chain_id,
+                nonces: Mapping::default(),
+                flash_fee_bps: flash_fee_bps.min(MAX_BPS),
+                fee_recipient,
+                flash_loan_active: false,
+                flash_minted: 0,
+                deposit_call: None,
+                rate_limit_charged: false,
+                pending_withdrawals: Mapping::default(),
+                total_pending: 0,
+                withdrawal_fallback: Mapping::default(),
             }
         }
 
@@ -174,21 +727,64 @@ pub mod wlunes {
         /// Internal helper to validate invariants.
         fn validate_invariants(&self) -> Result<(), Error> {
             // Ensure total supply is consistent
-            if self.total_supply > Balance::MAX / 2 {
+            if self.total_supply > self.config.max_total_supply {
+                return Err(Error::InvalidState);
+            }
+            
+            // Validate contract's native balance backs the total supply (1:1 invariant)
+            // Excess balance (burns, forced transfers) is surplus, see `skim`
+            // WLUNES flash-minted by a running loan is backed once it is burned
+            let backed_supply = self.total_supply.checked_sub(self.flash_minted).ok_or(Error::InvalidState)?;
+            if self.backing_balance() < backed_supply {
                 return Err(Error::InvalidState);
             }
             
-            // Validate contract's native balance matches total supply (1:1 invariant)
-            // Skip this check in test environment as balance() behavior differs
+            Ok(())
+        }
+
+        /// Native balance available to back WLUNES: the contract's balance
+        /// net of the existential deposit it must keep to stay alive and of
+        /// the LUNES owed to pending withdrawal claims
+        fn backing_balance(&self) -> Balance {
+            self.reducible_balance().saturating_sub(self.total_pending)
+        }
+
+        /// The contract's balance net of its existential deposit
+        fn reducible_balance(&self) -> Balance {
+            // On-chain, balance() already excludes the existential deposit
             #[cfg(not(test))]
             {
-                let contract_balance = self.env().balance();
-                if contract_balance != self.total_supply {
-                    return Err(Error::InvalidState);
-                }
+                self.env().balance()
+            }
+
+            // The off-chain test environment reports the full free balance
+            #[cfg(test)]
+            {
+                self.env().balance().saturating_sub(self.env().minimum_balance())
+            }
+        }
+
+        /// Maps a failed native transfer of `amount` to a descriptive error
+        fn native_transfer_error(&self, amount: Balance) -> Error {
+            if amount < self.env().minimum_balance() {
+                Error::NativeTransferBelowExistentialDeposit
+            } else {
+                Error::NativeTransferFailed
+            }
+        }
+
+        /// Sends `amount` native LUNES to `recipient`
+        fn transfer_native(&self, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            // The off-chain test environment accepts every transfer, so tests
+            // name the recipients whose transfers fail
+            #[cfg(test)]
+            if tests::rejects_native_transfers_to(recipient) {
+                return Err(self.native_transfer_error(amount));
             }
             
-            Ok(())
+            self.env()
+                .transfer(recipient, amount)
+                .map_err(|_| self.native_transfer_error(amount))
         }
 
         /// Reentrancy guard modifier - prevents reentrant calls
@@ -211,49 +807,108 @@ pub mod wlunes {
             self.reentrancy_guard = false;
         }
 
-        /// Validate gas limit to prevent DoS attacks
-        fn validate_gas_limit(&self) -> Result<(), Error> {
-            // Skip gas validation in test environment as gas_left() is not supported
+        /// Blocks wrapping and unwrapping while a flash loan callback is running
+        fn ensure_no_flash_loan(&self) -> Result<(), Error> {
+            if self.flash_loan_active {
+                self.env().emit_event(SecurityAlert {
+                    alert_type: 1, // Reentrancy
+                    account: self.env().caller(),
+                    details: 0,
+                });
+                return Err(Error::ReentrancyDetected);
+            }
+            Ok(())
+        }
+
+        /// Writes the root storage so reentrant calls observe the current state
+        fn flush_state(&self) {
+            ink::env::set_contract_storage(&<Self as ink::storage::traits::StorageKey>::KEY, self);
+        }
+
+        /// Reloads the root storage after a call that allowed reentry
+        fn reload_state(&mut self) -> Result<(), Error> {
+            *self = ink::env::get_contract_storage(&<Self as ink::storage::traits::StorageKey>::KEY)
+                .ok()
+                .flatten()
+                .ok_or(Error::InvalidState)?;
+            Ok(())
+        }
+
+        /// Validate the gas left against the needs of `operation`
+        ///
+        /// Fails early, with a clear error, only if the call cannot complete.
+        /// `proof_size` is not checked: ink! 5 reads the gas left through
+        /// `seal0::gas_left`, which returns `ref_time` only, and the full
+        /// weight (`seal1::gas_left`) is an unstable host function that
+        /// production runtimes do not enable.
+        fn validate_gas_limit(&self, operation: Operation) -> Result<(), Error> {
+            self.check_weight(operation, self.gas_left())
+        }
+
+        /// `ref_time` left for the current call
+        fn gas_left(&self) -> u64 {
             #[cfg(not(test))]
             {
-                let gas_left = self.env().gas_left();
-                // Only validate in production environment (when gas is actually limited)
-                if gas_left > 0 && gas_left < self.max_gas_limit {
-                    self.env().emit_event(SecurityAlert {
+                self.env().gas_left()
+            }
+            
+            // The off-chain test environment does not support gas_left(), so
+            // tests set the value
+            #[cfg(test)]
+            {
+                tests::gas_left()
+            }
+        }
+
+        /// Weight check behind `validate_gas_limit`
+        fn check_weight(&self, operation: Operation, ref_time_left: u64) -> Result<(), Error> {
+            if ref_time_left < operation.min_ref_time() {
+                return self.security_violation(
+                    self.config.gas_limit_mode,
+                    SecurityAlert {
                         alert_type: 2, // Gas limit
                         account: self.env().caller(),
-                        details: gas_left as Balance,
-                    });
-                    return Err(Error::GasLimitExceeded);
-                }
+                        details: ref_time_left as Balance,
+                    },
+                    Error::GasLimitExceeded,
+                );
             }
             Ok(())
         }
 
         /// Validate amount limits to prevent overflow and suspicious activity
         fn validate_amount_limits(&self, amount: Balance) -> Result<(), Error> {
-            // Maximum single transaction limit (10% of max supply)
-            const MAX_SINGLE_AMOUNT: Balance = Balance::MAX / 10;
-            
-            if amount > MAX_SINGLE_AMOUNT {
-                self.env().emit_event(SecurityAlert {
-                    alert_type: 3, // Suspicious activity
-                    account: self.env().caller(),
-                    details: amount,
-                });
-                return Err(Error::AmountTooLarge);
+            // Maximum single transaction limit (deploy-time configured)
+            if amount > self.config.max_transaction_amount {
+                return self.security_violation(
+                    self.config.amount_limit_mode,
+                    SecurityAlert {
+                        alert_type: 3, // Suspicious activity
+                        account: self.env().caller(),
+                        details: amount,
+                    },
+                    Error::AmountTooLarge,
+                );
             }
             Ok(())
         }
 
+        /// Emits `alert` and fails with `error`, unless `mode` only alerts
+        fn security_violation(&self, mode: CheckMode, alert: SecurityAlert, error: Error) -> Result<(), Error> {
+            self.env().emit_event(alert);
+            match mode {
+                CheckMode::Block => Err(error),
+                CheckMode::Alert => Ok(()),
+            }
+        }
+
         /// Enhanced address validation with additional security checks
         fn enhanced_address_validation(&self, address: AccountId) -> Result<(), Error> {
             // Check for zero address
             self.ensure_not_zero_address(address)?;
             
-            // In production, prevent direct contract self-interaction for transfers
-            // But allow it for internal operations like deposit/withdraw
-            // This check is context-sensitive and mainly for transfer operations
+            // Transfers to the contract's own address are not rejected here:
+            // `transfer_helper` turns them into an unwrap for the sender
             
             Ok(())
         }
@@ -261,82 +916,119 @@ pub mod wlunes {
         /// Advanced rate limiting validation - prevents spam and DoS attacks
         /// 
         /// Implements sophisticated rate limiting with:
-        /// - Per-account transaction cooldown periods
+        /// - Per-account transaction cooldown periods for non-contract callers
         /// - Suspicious pattern detection
         /// - Automatic security alerts for violations
         /// 
         /// This is a critical OWASP Top 10 2025 security feature.
         fn validate_rate_limiting(&mut self, caller: AccountId) -> Result<(), Error> {
-            // Skip rate limiting in test environment to allow tests to pass
-            #[cfg(test)]
-            {
-                let _ = caller; // Suppress unused variable warning in test
+            // The enclosing message already charged this operation
+            if self.rate_limit_charged {
                 return Ok(());
             }
             
-            #[cfg(not(test))]
-            {
-                let current_time = self.env().block_timestamp();
-                let last_tx = self.last_transaction.get(caller).unwrap_or(0);
-                
-                // Check cooldown period
-                if let Some(next_allowed) = last_tx.checked_add(self.transaction_cooldown) {
+            // Contract callers such as routers and pools act for many users,
+            // so only accounts that are not contracts are charged
+            if self.env().is_contract(&caller) {
+                return Ok(());
+            }
+            
+            let current_time = self.env().block_timestamp();
+            
+            // Check cooldown period (an account's first operation has none)
+            if let Some(last_tx) = self.last_transaction.get(caller) {
+                if let Some(next_allowed) = last_tx.checked_add(self.config.transaction_cooldown) {
                     if current_time < next_allowed {
                         let time_diff = current_time.saturating_sub(last_tx);
-                        self.env().emit_event(SecurityAlert {
-                            alert_type: 4, // Rate limiting violation
-                            account: caller,
-                            details: time_diff as Balance,
-                        });
-                        return Err(Error::OperationBlocked);
+                        self.security_violation(
+                            self.config.rate_limit_mode,
+                            SecurityAlert {
+                                alert_type: 4, // Rate limiting violation
+                                account: caller,
+                                details: time_diff as Balance,
+                            },
+                            Error::OperationBlocked,
+                        )?;
                     }
                 }
-                
-                // Update transaction tracking
-                self.last_transaction.insert(caller, &current_time);
-                
-                // Increment transaction counter for pattern analysis
-                let tx_count = self.transaction_count.get(caller).unwrap_or(0);
-                if let Some(new_count) = tx_count.checked_add(1) {
-                    self.transaction_count.insert(caller, &new_count);
-                }
-                
-                Ok(())
             }
+            
+            // Update transaction tracking
+            self.last_transaction.insert(caller, &current_time);
+            
+            // Draw from the per-account rate limit bucket
+            self.consume_rate_limit(caller)
+        }
+
+        /// Token-bucket rate limiter shared by transfers, deposits and withdrawals
+        ///
+        /// Each operation takes one token; buckets refill `rate_limit_burst`
+        /// tokens per `rate_limit_window`, so the limit decays over time.
+        fn consume_rate_limit(&mut self, account: AccountId) -> Result<(), Error> {
+            let burst = self.config.rate_limit_burst;
+            if burst == 0 {
+                return Ok(());
+            }
+            
+            let now = self.env().block_timestamp();
+            let (tokens, updated_at) = self.rate_limits.get(account).unwrap_or((burst, now));
+            
+            // Refill proportionally to the elapsed time, capped at the burst size
+            let elapsed = now.saturating_sub(updated_at) as u128;
+            let refill = elapsed
+                .saturating_mul(u128::from(burst))
+                .checked_div(u128::from(self.config.rate_limit_window.max(1)))
+                .unwrap_or(0);
+            let available = u32::try_from(u128::from(tokens).saturating_add(refill).min(u128::from(burst)))
+                .unwrap_or(burst);
+            
+            if available == 0 {
+                self.security_violation(
+                    self.config.rate_limit_mode,
+                    SecurityAlert {
+                        alert_type: 4, // Rate limiting violation
+                        account,
+                        details: elapsed,
+                    },
+                    Error::OperationBlocked,
+                )?;
+            }
+            
+            // Move the clock by the time the added tokens took to earn, so the
+            // progress towards the next token is kept
+            let earned_time = refill
+                .saturating_mul(u128::from(self.config.rate_limit_window))
+                .checked_div(u128::from(burst))
+                .unwrap_or(0);
+            let updated_at = updated_at
+                .saturating_add(u64::try_from(earned_time).unwrap_or(u64::MAX))
+                .min(now);
+            self.rate_limits.insert(account, &(available.saturating_sub(1), updated_at));
+            
+            Ok(())
         }
         
         /// Advanced suspicious pattern detection
         /// 
         /// Analyzes transaction patterns to detect:
-        /// - Rapid successive transactions
         /// - Unusual transaction volumes
-        /// - Potential bot or automated activity
+        /// - Potential attacks using extreme amounts
         /// 
         /// Enhances OWASP SC09 (Denial of Service) protection.
         fn detect_suspicious_patterns(&self, caller: AccountId, amount: Balance) -> Result<(), Error> {
-            let tx_count = self.transaction_count.get(caller).unwrap_or(0);
-            let current_time = self.env().block_timestamp();
-            let time_since_deployment = current_time.saturating_sub(self.deployment_timestamp);
-            
-            // Pattern 1: Too many transactions in short time (potential bot)
-            if tx_count > 100 && time_since_deployment < 3600000 { // 1 hour
-                self.env().emit_event(SecurityAlert {
-                    alert_type: 5, // Suspicious pattern
-                    account: caller,
-                    details: tx_count as Balance,
-                });
-                return Err(Error::OperationBlocked);
-            }
-            
-            // Pattern 2: Extremely large single transaction (potential attack)
-            let max_reasonable_amount = Balance::MAX / 1000; // 0.1% of max supply
-            if amount > max_reasonable_amount {
-                self.env().emit_event(SecurityAlert {
-                    alert_type: 6, // Large transaction
-                    account: caller,
-                    details: amount,
-                });
-                return Err(Error::AmountTooLarge);
+            // Transaction frequency is bounded by the rate limiter (`consume_rate_limit`)
+            
+            // Pattern: Extremely large single transaction (potential attack)
+            if amount > self.config.suspicious_amount {
+                return self.security_violation(
+                    self.config.pattern_mode,
+                    SecurityAlert {
+                        alert_type: 6, // Large transaction
+                        account: caller,
+                        details: amount,
+                    },
+                    Error::AmountTooLarge,
+                );
             }
             
             Ok(())
@@ -346,9 +1038,10 @@ pub mod wlunes {
         /// 
         /// Performs all security checks in optimal order:
         /// 1. Basic address validation
-        /// 2. Gas limit validation
-        /// 3. Rate limiting validation
-        /// 4. Suspicious pattern detection
+        /// 2. Rate limiting validation
+        /// 3. Suspicious pattern detection
+        ///
+        /// The gas limit is validated on entry of each message instead.
         /// 
         /// This function implements multiple OWASP Top 10 2025 protections.
         fn validate_transaction_context(&mut self, amount: Balance) -> Result<(), Error> {
@@ -357,25 +1050,55 @@ pub mod wlunes {
             // Step 1: Basic caller validation (zero address check)
             self.ensure_not_zero_address(caller)?;
             
-            // Step 2: Gas limit validation (production-aware)
-            self.validate_gas_limit()?;
-            
-            // Step 3: Rate limiting validation (prevents spam/DoS)
+            // Step 2: Rate limiting validation (prevents spam/DoS)
             self.validate_rate_limiting(caller)?;
             
-            // Step 4: Suspicious pattern detection (advanced security)
+            // Step 3: Suspicious pattern detection (advanced security)
             self.detect_suspicious_patterns(caller, amount)?;
             
             Ok(())
         }
 
         /// Deposits native LUNES tokens and mints WLUNES tokens at a 1:1 ratio.
+        ///
+        /// There is no payable fallback: ink! only allows a wildcard selector
+        /// next to a single `selector = @` message, so LUNES must be wrapped
+        /// through this (or another payable) message to be minted.
         #[ink(message, payable)]
         pub fn deposit(&mut self) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Deposit)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            let result = self.deposit_internal(caller, amount);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// PSP22Wrapper: deposits the transferred native LUNES and mints the
+        /// same amount of WLUNES to `account`.
+        ///
+        /// `amount` must match the value sent with the call.
+        #[ink(message, payable)]
+        pub fn deposit_for(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::DepositFor)?;
+            
             // Security: Reentrancy guard
             self.reentrancy_guard()?;
             
-            let result = self.deposit_internal();
+            let result = if self.env().transferred_value() != amount {
+                Err(Error::AmountMismatch)
+            } else {
+                self.deposit_internal(account, amount)
+            };
             
             // Always release reentrancy guard
             self.release_reentrancy_guard();
@@ -392,28 +1115,29 @@ pub mod wlunes {
         /// - Gas optimization with security
         /// 
         /// This function exemplifies OWASP Top 10 2025 best practices.
-        fn deposit_internal(&mut self) -> Result<(), Error> {
-            let amount = self.env().transferred_value();
+        fn deposit_internal(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
             if amount == 0 {
                 return Err(Error::ZeroAmount);
             }
+            self.ensure_no_flash_loan()?;
 
             let caller = self.env().caller();
             
             // Advanced security validations with rate limiting and pattern detection
             self.validate_transaction_context(amount)?;
             self.validate_amount_limits(amount)?;
+            self.enhanced_address_validation(account)?;
             
             // Gas optimization: Single storage read
-            let caller_balance = self.balance_of(caller);
+            let account_balance = self.balance_of(account);
             
             // Security: Check for overflow before state changes
             let new_total_supply = self.total_supply.checked_add(amount).ok_or(Error::Overflow)?;
-            let new_caller_balance = caller_balance.checked_add(amount).ok_or(Error::Overflow)?;
+            let new_account_balance = account_balance.checked_add(amount).ok_or(Error::Overflow)?;
             
             // Effects: Update state
             self.total_supply = new_total_supply;
-            self.balances.insert(caller, &new_caller_balance);
+            self.balances.insert(account, &new_account_balance);
             
             // Security: Validate invariants
             self.validate_invariants()?;
@@ -423,12 +1147,13 @@ pub mod wlunes {
             // Emit enhanced events
             self.env().emit_event(Transfer {
                 from: None,
-                to: Some(caller),
+                to: Some(account),
                 value: amount,
             });
             
             self.env().emit_event(Deposit {
-                account: caller,
+                payer: caller,
+                account,
                 amount,
                 timestamp,
             });
@@ -439,57 +1164,249 @@ pub mod wlunes {
         /// Withdraws WLUNES tokens and transfers native LUNES tokens at a 1:1 ratio.
         #[ink(message)]
         pub fn withdraw(&mut self, amount: Balance) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Withdraw)?;
+            
             // Security: Reentrancy guard
             self.reentrancy_guard()?;
             
-            let result = self.withdraw_internal(amount);
+            let caller = self.env().caller();
+            let result = self.withdraw_internal(caller, caller, amount);
             
             // Always release reentrancy guard
             self.release_reentrancy_guard();
             
             result
         }
-        
-        /// Internal withdraw logic with enterprise-grade security
-        /// 
-        /// Implements comprehensive security validations:
-        /// - Rate limiting and spam protection
-        /// - Suspicious pattern detection
+
+        /// PSP22Wrapper: burns `amount` of the caller's WLUNES and sends the
+        /// same amount of native LUNES to `account`.
+        #[ink(message)]
+        pub fn withdraw_to(&mut self, account: AccountId, amount: Balance) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::WithdrawTo)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let caller = self.env().caller();
+            let result = self.withdraw_internal(caller, account, amount);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Burns `amount` of `owner`'s WLUNES using the caller's allowance and
+        /// sends the same amount of native LUNES to `recipient`.
+        #[ink(message)]
+        pub fn withdraw_from(&mut self, owner: AccountId, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::WithdrawFrom)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.withdraw_from_internal(owner, recipient, amount);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal allowance-based withdraw logic
+        fn withdraw_from_internal(&mut self, owner: AccountId, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+
+            let caller = self.env().caller();
+            self.ensure_not_zero_address(owner)?;
+            
+            // Checks & Effects: Consume allowance unless unwrapping own tokens
+            if owner != caller {
+                self.spend_allowance(owner, caller, amount)?;
+            }
+            
+            self.withdraw_internal(owner, recipient, amount)
+        }
+
+        /// Decreases the allowance `owner` granted to `spender` by `value`
+        /// and emits `Approval` with the remaining allowance.
+        ///
+        /// An allowance of `Balance::MAX` is unlimited and is never decremented.
+        fn spend_allowance(&mut self, owner: AccountId, spender: AccountId, value: Balance) -> Result<(), Error> {
+            let allowance = self.allowance(owner, spender);
+            
+            // Gas optimization: Unlimited allowance skips the storage write
+            if allowance == Balance::MAX {
+                return Ok(());
+            }
+            
+            if allowance < value {
+                return Err(Error::InsufficientAllowance);
+            }
+            
+            // Security: Check for underflow before updating allowance
+            let new_allowance = allowance.checked_sub(value).ok_or(Error::Overflow)?;
+            self.allowances.insert((owner, spender), &new_allowance);
+            
+            // Emit approval event with the remaining allowance
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value: new_allowance,
+            });
+            
+            Ok(())
+        }
+        
+        /// Internal withdraw logic with enterprise-grade security
+        /// 
+        /// Implements comprehensive security validations:
+        /// - Rate limiting and spam protection
+        /// - Suspicious pattern detection
         /// - Advanced amount validation
         /// - Gas optimization with security
         /// 
         /// This function exemplifies OWASP Top 10 2025 best practices.
-        fn withdraw_internal(&mut self, amount: Balance) -> Result<(), Error> {
-            if amount == 0 {
-                return Err(Error::ZeroAmount);
+        fn withdraw_internal(&mut self, owner: AccountId, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            self.unwrap_internal(owner, recipient, amount)?;
+            
+            // Interactions: Transfer native LUNES tokens to recipient (last step)
+            if let Err(error) = self.transfer_native(recipient, amount) {
+                if !self.withdrawal_fallback(owner) {
+                    return Err(error);
+                }
+                
+                // Effects: Opted-in owners can claim the LUNES later
+                let pending = self.pending_withdrawal(owner).checked_add(amount).ok_or(Error::Overflow)?;
+                self.total_pending = self.total_pending.checked_add(amount).ok_or(Error::Overflow)?;
+                self.pending_withdrawals.insert(owner, &pending);
+                
+                self.env().emit_event(WithdrawalDeferred {
+                    account: owner,
+                    recipient,
+                    amount,
+                });
             }
+            
+            Ok(())
+        }
 
+        /// Opts the caller in or out of pending withdrawal claims.
+        ///
+        /// When enabled, an unwrap whose native transfer fails still burns the
+        /// WLUNES and records the LUNES as a pending claim for the caller,
+        /// collectable with `claim_withdrawal`. Otherwise the unwrap reverts.
+        #[ink(message)]
+        pub fn set_withdrawal_fallback(&mut self, enabled: bool) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::SetWithdrawalFallback)?;
+            
             let caller = self.env().caller();
+            self.ensure_not_zero_address(caller)?;
+            
+            if enabled {
+                self.withdrawal_fallback.insert(caller, &true);
+            } else {
+                self.withdrawal_fallback.remove(caller);
+            }
+            
+            Ok(())
+        }
+
+        /// Returns whether `owner` opted into pending withdrawal claims.
+        #[ink(message)]
+        pub fn withdrawal_fallback(&self, owner: AccountId) -> bool {
+            self.withdrawal_fallback.get(owner).unwrap_or(false)
+        }
+
+        /// Returns the native LUNES `owner` can collect with `claim_withdrawal`.
+        #[ink(message)]
+        pub fn pending_withdrawal(&self, owner: AccountId) -> Balance {
+            self.pending_withdrawals.get(owner).unwrap_or(0)
+        }
+
+        /// Sends the caller's pending withdrawal to the caller.
+        #[ink(message)]
+        pub fn claim_withdrawal(&mut self) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::ClaimWithdrawal)?;
             
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.claim_withdrawal_internal();
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal claim logic
+        fn claim_withdrawal_internal(&mut self) -> Result<(), Error> {
+            self.ensure_no_flash_loan()?;
+            
+            let caller = self.env().caller();
+            let amount = self.pending_withdrawal(caller);
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            
+            // Effects: Clear the claim before paying it
+            self.total_pending = self.total_pending.checked_sub(amount).ok_or(Error::Overflow)?;
+            self.pending_withdrawals.remove(caller);
+            
+            self.env().emit_event(WithdrawalClaimed {
+                account: caller,
+                amount,
+            });
+            
+            // Interactions: Transfer native LUNES tokens (last step)
+            self.transfer_native(caller, amount)?;
+            
+            // Security: Remaining supply and claims must still be backed
+            self.validate_invariants()
+        }
+
+        /// Checks and effects of an unwrap: burns `amount` of `owner`'s WLUNES
+        /// and emits the events. The caller sends the native LUNES.
+        fn unwrap_internal(&mut self, owner: AccountId, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.ensure_no_flash_loan()?;
+
             // Advanced security validations with rate limiting and pattern detection
             self.validate_transaction_context(amount)?;
             self.validate_amount_limits(amount)?;
+            self.enhanced_address_validation(recipient)?;
             
             // Gas optimization: Single storage read
-            let caller_balance = self.balance_of(caller);
+            let owner_balance = self.balance_of(owner);
             
             // Checks: Validate sufficient balance
-            if caller_balance < amount {
+            if owner_balance < amount {
                 return Err(Error::InsufficientBalance);
             }
             
             // Security: Validate contract has enough native balance for withdrawal
-            if self.env().balance() < amount {
-                return Err(Error::InsufficientBalance);
+            // without dipping into its existential deposit (checked before burning)
+            if self.backing_balance() < amount {
+                return Err(Error::InsufficientReserve);
             }
 
             // Security: Check for underflow before state changes
             let new_total_supply = self.total_supply.checked_sub(amount).ok_or(Error::Overflow)?;
-            let new_caller_balance = caller_balance.checked_sub(amount).ok_or(Error::Overflow)?;
+            let new_owner_balance = owner_balance.checked_sub(amount).ok_or(Error::Overflow)?;
             
-            // Effects: Burn WLUNES tokens from caller
+            // Effects: Burn WLUNES tokens from owner
             self.total_supply = new_total_supply;
-            self.balances.insert(caller, &new_caller_balance);
+            self.balances.insert(owner, &new_owner_balance);
             
             // Security: Validate invariants
             self.validate_invariants()?;
@@ -498,176 +1415,1099 @@ pub mod wlunes {
             
             // Emit enhanced events
             self.env().emit_event(Transfer {
-                from: Some(caller),
+                from: Some(owner),
+                to: None,
+                value: amount,
+            });
+            
+            self.env().emit_event(Withdrawal {
+                account: owner,
+                recipient,
+                amount,
+                timestamp,
+            });
+
+            Ok(())
+        }
+
+        /// Wraps the transferred native LUNES for the caller, raises `target`'s
+        /// allowance by the deposited amount and calls `target` with `selector`
+        /// followed by the SCALE-encoded arguments in `data`.
+        ///
+        /// The reentrancy guard stays engaged: the target may only call back
+        /// into `transfer_from` with the caller as `from`. Any unspent part of
+        /// the deposit's approval is revoked afterwards. The target must return
+        /// `()` or a `Result`; an `Err` reverts the whole call.
+        #[ink(message, payable)]
+        pub fn deposit_and_call(&mut self, target: AccountId, selector: [u8; 4], data: Vec<u8>) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::DepositAndCall)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.deposit_and_call_internal(target, selector, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal wrap-and-call logic
+        fn deposit_and_call_internal(&mut self, target: AccountId, selector: [u8; 4], data: Vec<u8>) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            
+            // Checks & Effects: Mint and approve before the external call
+            self.enhanced_address_validation(target)?;
+            self.deposit_internal(caller, amount)?;
+            let previous_allowance = self.allowance(caller, target);
+            self.approve_helper(caller, target, previous_allowance.saturating_add(amount))?;
+            
+            // Interactions: The target may only pull the caller's WLUNES
+            self.deposit_call = Some((caller, target));
+            self.flush_state();
+            
+            let result = self.forward_call(target, 0, selector, data, CallFlags::ALLOW_REENTRY);
+            
+            self.reload_state()?;
+            self.deposit_call = None;
+            
+            // Effects: Revoke what is left of the deposit's approval
+            if self.allowance(caller, target) > previous_allowance {
+                self.approve_helper(caller, target, previous_allowance)?;
+            }
+            
+            result
+        }
+
+        /// Burns `amount` of the caller's WLUNES and calls `target` with the
+        /// same amount of native LUNES attached, using `selector` followed by
+        /// the SCALE-encoded arguments in `data`.
+        ///
+        /// The target cannot call back into WLUNES. It must return `()` or a
+        /// `Result`; an `Err` reverts the whole call.
+        #[ink(message)]
+        pub fn withdraw_and_call(
+            &mut self,
+            amount: Balance,
+            target: AccountId,
+            selector: [u8; 4],
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::WithdrawAndCall)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let caller = self.env().caller();
+            let result = self
+                .unwrap_internal(caller, target, amount)
+                .and_then(|()| self.forward_call(target, amount, selector, data, CallFlags::empty()));
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Calls `target` with `selector` and raw `data`, attaching `value`.
+        fn forward_call(
+            &self,
+            target: AccountId,
+            value: Balance,
+            selector: [u8; 4],
+            data: Vec<u8>,
+            flags: CallFlags,
+        ) -> Result<(), Error> {
+            let result = build_call::<DefaultEnvironment>()
+                .call(target)
+                .transferred_value(value)
+                .call_flags(flags)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(RawCallData(data)))
+                .returns::<RawCallOutput>()
+                .try_invoke();
+            
+            match result {
+                Ok(Ok(output)) if output.succeeded() => Ok(()),
+                _ => Err(Error::CallFailed),
+            }
+        }
+
+        /// Helper function for transferring tokens.
+        fn transfer_helper(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), Error> {
+            // Gas optimization: Single storage reads
+            let from_balance = self.balance_of(from);
+            
+            // Checks: Validate sufficient balance
+            if from_balance < value {
+                return Err(Error::InsufficientBalance);
+            }
+            
+            // PSP22: Zero-value and self transfers succeed without touching balances
+            if value == 0 || from == to {
+                self.env().emit_event(Transfer {
+                    from: Some(from),
+                    to: Some(to),
+                    value,
+                });
+                return Ok(());
+            }
+            
+            // WLUNES sent to the token contract itself is unwrapped for the sender
+            if to == self.env().account_id() {
+                return self.unwrap_sent_to_self(from, value);
+            }
+            
+            let to_balance = self.balance_of(to);
+            
+            // Security: Check for overflow/underflow before state changes
+            let new_from_balance = from_balance.checked_sub(value).ok_or(Error::Overflow)?;
+            let new_to_balance = to_balance.checked_add(value).ok_or(Error::Overflow)?;
+            
+            // Checks: Contract recipients must accept the transfer
+            self.safe_transfer_check(from, to, value, data)?;
+            
+            // Effects: Update balances
+            self.balances.insert(from, &new_from_balance);
+            self.balances.insert(to, &new_to_balance);
+            
+            // Security: Validate invariants (total supply unchanged)
+            self.validate_invariants()?;
+            
+            // Emit transfer event
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            
+            Ok(())
+        }
+
+        /// Unwraps `value` of `from`'s WLUNES sent to the token contract itself
+        /// and pays the LUNES to `from`. The transfer already charged the rate
+        /// limiter, so the unwrap is not charged again.
+        fn unwrap_sent_to_self(&mut self, from: AccountId, value: Balance) -> Result<(), Error> {
+            let charged = core::mem::replace(&mut self.rate_limit_charged, true);
+            let result = self.withdraw_internal(from, from, value);
+            self.rate_limit_charged = charged;
+            result
+        }
+
+        /// Returns the checked sum of a batch, enforcing `MAX_BATCH_SIZE`.
+        fn batch_total(recipients: &[(AccountId, Balance)]) -> Result<Balance, Error> {
+            if recipients.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge);
+            }
+            recipients
+                .iter()
+                .try_fold(0, |total: Balance, (_, value)| total.checked_add(*value))
+                .ok_or(Error::Overflow)
+        }
+
+        /// Helper function for batch transfers: debits `from` once and credits
+        /// each recipient.
+        fn batch_transfer_helper(&mut self, from: AccountId, recipients: Vec<(AccountId, Balance)>, data: Vec<u8>) -> Result<(), Error> {
+            let total = Self::batch_total(&recipients)?;
+            self.validate_amount_limits(total)?;
+            for (to, _) in recipients.iter() {
+                self.ensure_not_zero_address(*to)?;
+            }
+            
+            // Gas optimization: Single read and write of the sender balance
+            let from_balance = self.balance_of(from);
+            
+            // Checks: Validate sufficient balance for the whole batch
+            if from_balance < total {
+                return Err(Error::InsufficientBalance);
+            }
+            
+            // Entries sent to the token contract itself are unwrapped for the sender
+            let account_id = self.env().account_id();
+            let unwrap_total: Balance = recipients
+                .iter()
+                .filter(|(to, _)| *to == account_id)
+                .map(|(_, value)| *value)
+                .sum();
+            
+            // Effects: Debit sender once for the credited entries
+            let credited_total = total.checked_sub(unwrap_total).ok_or(Error::Overflow)?;
+            self.balances.insert(from, &from_balance.checked_sub(credited_total).ok_or(Error::Overflow)?);
+            
+            for (to, value) in recipients.into_iter().filter(|(to, _)| *to != account_id) {
+                // Checks: Contract recipients must accept the transfer
+                self.safe_transfer_check(from, to, value, data.clone())?;
+                
+                // Effects: Credit recipient
+                let to_balance = self.balance_of(to);
+                self.balances.insert(to, &to_balance.checked_add(value).ok_or(Error::Overflow)?);
+                
+                self.env().emit_event(Transfer {
+                    from: Some(from),
+                    to: Some(to),
+                    value,
+                });
+            }
+            
+            // Security: Validate invariants (total supply unchanged)
+            self.validate_invariants()?;
+            
+            // Interactions: Unwrap last, it sends native LUNES
+            if unwrap_total > 0 {
+                self.unwrap_sent_to_self(from, unwrap_total)?;
+            }
+            
+            Ok(())
+        }
+
+        /// PSP22Receiver safe-transfer check.
+        ///
+        /// If `to` is a contract, calls its `PSP22Receiver::before_received`
+        /// hook and fails if the receiver rejects the transfer or does not
+        /// implement the hook. Reentry into WLUNES is not allowed during the call.
+        fn safe_transfer_check(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), Error> {
+            if !self.env().is_contract(&to) {
+                return Ok(());
+            }
+            
+            let operator = self.env().caller();
+            let result = build_call::<DefaultEnvironment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22Receiver::before_received")))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<Result<(), PSP22ReceiverError>>()
+                .try_invoke();
+            
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(PSP22ReceiverError::TransferRejected(reason)))) => {
+                    Err(Error::SafeTransferCheckFailed(reason))
+                }
+                // Missing hook, trapped receiver or undecodable reply
+                _ => Err(Error::SafeTransferCheckFailed(RECEIVER_CALL_FAILED.into())),
+            }
+        }
+
+        /// Helper function for setting the allowance of `spender` over `owner`'s tokens.
+        fn approve_helper(&mut self, owner: AccountId, spender: AccountId, value: Balance) -> Result<(), Error> {
+            // Security: Validate addresses
+            self.ensure_not_zero_address(owner)?;
+            self.ensure_not_zero_address(spender)?;
+            
+            // Security: Prevent self-approval with non-zero value (potential attack vector)
+            if value > 0 && owner == spender {
+                return Err(Error::InvalidState);
+            }
+            
+            // Effects: Update allowance
+            self.allowances.insert((owner, spender), &value);
+            
+            // Emit approval event
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+            
+            Ok(())
+        }
+
+        /// Transfers WLUNES from the caller to every `(to, value)` entry.
+        ///
+        /// The sender is validated and debited once, and one `Transfer` is
+        /// emitted per recipient. The batch is atomic and limited to
+        /// `MAX_BATCH_SIZE` entries.
+        #[ink(message)]
+        pub fn batch_transfer(&mut self, recipients: Vec<(AccountId, Balance)>, data: Vec<u8>) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::BatchTransfer { recipients: u32::try_from(recipients.len()).unwrap_or(u32::MAX) })?;
+            
+            let from = self.env().caller();
+            
+            // Enhanced security validations (once for the whole batch)
+            self.enhanced_address_validation(from)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroSenderAddress))?;
+            self.validate_rate_limiting(from)?;
+            
+            // Security: Reentrancy guard (receiver hook is an external call)
+            self.reentrancy_guard()?;
+            
+            let result = self.batch_transfer_helper(from, recipients, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result.map_err(|e| e.into_psp22(PSP22Error::ZeroRecipientAddress))
+        }
+
+        /// Transfers WLUNES from `from` to every `(to, value)` entry using the
+        /// caller's allowance, which is spent once for the batch total.
+        #[ink(message)]
+        pub fn batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            recipients: Vec<(AccountId, Balance)>,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::BatchTransferFrom { recipients: u32::try_from(recipients.len()).unwrap_or(u32::MAX) })?;
+            
+            let caller = self.env().caller();
+            
+            // Enhanced security validations (once for the whole batch)
+            self.enhanced_address_validation(caller)?;
+            self.enhanced_address_validation(from)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroSenderAddress))?;
+            self.validate_rate_limiting(caller)?;
+            
+            // Update allowance for the batch total
+            let total = Self::batch_total(&recipients)?;
+            self.spend_allowance(from, caller, total)?;
+            
+            // Security: Reentrancy guard (receiver hook is an external call)
+            self.reentrancy_guard()?;
+            
+            let result = self.batch_transfer_helper(from, recipients, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result.map_err(|e| e.into_psp22(PSP22Error::ZeroRecipientAddress))
+        }
+
+        /// Executes `calls` in order with the caller as the actor.
+        ///
+        /// Native LUNES attached to the call is wrapped for the caller before
+        /// the first call runs, so the batch can spend it. Returns one result
+        /// per call. The batch is atomic: if any call fails, everything
+        /// reverts and the error carries the results up to the failing call.
+        /// At most `MAX_BATCH_SIZE` calls are accepted.
+        #[ink(message, payable)]
+        pub fn multicall(&mut self, calls: Vec<Call>) -> Result<Vec<Result<(), Error>>, MulticallError> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Multicall { calls: u32::try_from(calls.len()).unwrap_or(u32::MAX) })?;
+            
+            // Security: One reentrancy guard for the whole batch
+            self.reentrancy_guard()?;
+            
+            let result = self.multicall_internal(calls);
+            
+            // Always release reentrancy guard
+            self.rate_limit_charged = false;
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal multicall logic
+        fn multicall_internal(&mut self, calls: Vec<Call>) -> Result<Vec<Result<(), Error>>, MulticallError> {
+            if calls.len() > MAX_BATCH_SIZE {
+                return Err(Error::BatchTooLarge.into());
+            }
+            
+            let caller = self.env().caller();
+            self.enhanced_address_validation(caller)?;
+            
+            // Security: Rate limit the batch once
+            self.validate_rate_limiting(caller)?;
+            self.rate_limit_charged = true;
+            
+            let value = self.env().transferred_value();
+            if value > 0 {
+                self.deposit_internal(caller, value)?;
+            }
+            
+            let mut results = Vec::with_capacity(calls.len());
+            for call in calls {
+                if let Err(error) = self.execute_call(caller, call) {
+                    results.push(Err(error.clone()));
+                    return Err(MulticallError { results, error });
+                }
+                results.push(Ok(()));
+            }
+            
+            Ok(results)
+        }
+
+        /// Runs a single multicall operation through the shared helpers.
+        fn execute_call(&mut self, caller: AccountId, call: Call) -> Result<(), Error> {
+            match call {
+                Call::Transfer { to, value, data } => {
+                    self.enhanced_address_validation(to)?;
+                    self.validate_amount_limits(value)?;
+                    self.transfer_helper(caller, to, value, data)
+                }
+                Call::TransferFrom { from, to, value, data } => {
+                    self.enhanced_address_validation(from)?;
+                    self.enhanced_address_validation(to)?;
+                    self.validate_amount_limits(value)?;
+                    self.spend_allowance(from, caller, value)?;
+                    self.transfer_helper(from, to, value, data)
+                }
+                Call::Approve { spender, value } => self.approve_helper(caller, spender, value),
+                Call::Withdraw { recipient, amount } => self.withdraw_internal(caller, recipient, amount),
+            }
+        }
+
+        /// Sets the allowance of `spender` over `owner`'s tokens using a
+        /// signature from `owner` instead of an `approve` call.
+        ///
+        /// The signed message is the blake2-256 hash of the SCALE-encoded
+        /// `(domain_separator, owner, spender, value, nonce, deadline)` payload,
+        /// where `nonce` is `nonces(owner)`. Accepts sr25519 and ECDSA signatures.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            signature: Signature,
+        ) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Permit)?;
+            
+            // Checks: Signatures must be bound to a chain
+            self.ensure_chain_id()?;
+            
+            // Checks: Payload must not be expired
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::SignatureExpired);
+            }
+            
+            let nonce = self.nonces(owner);
+            let payload = PermitPayload {
+                domain_separator: self.domain_separator(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            };
+            let message = self.env().hash_encoded::<Blake2x256, _>(&payload);
+            self.verify_signature(owner, &message, &signature)?;
+            
+            // Effects: Consume nonce before updating allowance
+            self.nonces.insert(owner, &nonce.checked_add(1).ok_or(Error::Overflow)?);
+            
+            self.approve_helper(owner, spender, value)
+        }
+
+        /// Executes an owner-signed `transfer`, `approve` or `withdraw` intent
+        /// submitted by a relayer, paying the relayer `fee` WLUNES from the
+        /// owner's balance.
+        ///
+        /// The signed message is the blake2-256 hash of the SCALE-encoded
+        /// `(domain_separator, b"WLUNES::execute_signed", intent)`. Each intent
+        /// takes one token from the owner's rate limit bucket, not the relayer's.
+        #[ink(message)]
+        pub fn execute_signed(&mut self, intent: SignedIntent, fee: Balance, signature: Signature) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::ExecuteSigned)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.execute_signed_internal(intent, fee, signature);
+            
+            // Always release reentrancy guard
+            self.rate_limit_charged = false;
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal signed intent logic
+        fn execute_signed_internal(&mut self, intent: SignedIntent, fee: Balance, signature: Signature) -> Result<(), Error> {
+            let owner = intent.owner;
+            let relayer = self.env().caller();
+            
+            // Checks: Signatures must be bound to a chain
+            self.ensure_chain_id()?;
+            
+            // Checks: Deadline, fee cap and nonce
+            if self.env().block_timestamp() > intent.deadline {
+                return Err(Error::SignatureExpired);
+            }
+            if fee > intent.max_fee {
+                return Err(Error::FeeTooHigh);
+            }
+            let nonce = self.nonces(owner);
+            if intent.nonce != nonce {
+                return Err(Error::InvalidNonce);
+            }
+            
+            let message = self
+                .env()
+                .hash_encoded::<Blake2x256, _>(&(self.domain_separator(), SIGNED_INTENT_TAG, &intent));
+            self.verify_signature(owner, &message, &signature)?;
+            
+            // Security: Rate limit the owner once for the whole intent
+            self.validate_rate_limiting(owner)?;
+            self.rate_limit_charged = true;
+            
+            // Effects: Consume nonce before executing anything
+            self.nonces.insert(owner, &nonce.checked_add(1).ok_or(Error::Overflow)?);
+            
+            // Pay the relayer from the owner's balance
+            if fee > 0 {
+                self.transfer_helper(owner, relayer, fee, Vec::new())?;
+            }
+            
+            self.env().emit_event(SignedIntentExecuted {
+                owner,
+                relayer,
+                nonce,
+                fee,
+            });
+            
+            match intent.action {
+                SignedAction::Transfer { to, value } => {
+                    self.enhanced_address_validation(to)?;
+                    self.validate_amount_limits(value)?;
+                    self.transfer_helper(owner, to, value, Vec::new())?;
+                }
+                SignedAction::Approve { spender, value } => {
+                    self.approve_helper(owner, spender, value)?;
+                }
+                SignedAction::Withdraw { recipient, amount } => {
+                    // Interactions: Native transfer happens last
+                    self.withdraw_internal(owner, recipient, amount)?;
+                }
+            }
+            
+            Ok(())
+        }
+
+        /// Flash-mints `amount` WLUNES to `receiver` and calls its
+        /// `PSP22FlashBorrower::on_flash_loan` hook.
+        ///
+        /// Before returning, the receiver must have approved this contract for
+        /// `amount + flash_fee(amount)`. The `amount` is burned and the fee is
+        /// transferred to the fee recipient, so total supply ends where it
+        /// started. Wrapping and unwrapping are blocked during the callback.
+        #[ink(message)]
+        pub fn flash_loan(&mut self, receiver: AccountId, amount: Balance, data: Vec<u8>) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::FlashLoan)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.flash_loan_internal(receiver, amount, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal flash loan logic
+        fn flash_loan_internal(&mut self, receiver: AccountId, amount: Balance, data: Vec<u8>) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.ensure_no_flash_loan()?;
+            
+            let initiator = self.env().caller();
+            self.enhanced_address_validation(initiator)?;
+            self.enhanced_address_validation(receiver)?;
+            
+            // Checks: Amount within the supply ceiling, receiver can take the callback
+            if amount > self.max_flash_loan() {
+                return Err(Error::AmountTooLarge);
+            }
+            if !self.env().is_contract(&receiver) {
+                return Err(Error::FlashLoanFailed);
+            }
+            
+            let fee = self.flash_fee(amount);
+            let supply_before = self.total_supply;
+            
+            // Effects: Temporarily mint to the receiver
+            let receiver_balance = self.balance_of(receiver);
+            self.total_supply = supply_before.checked_add(amount).ok_or(Error::Overflow)?;
+            self.balances.insert(receiver, &receiver_balance.checked_add(amount).ok_or(Error::Overflow)?);
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(receiver),
+                value: amount,
+            });
+            
+            // Interactions: The receiver may call back into WLUNES (except
+            // wrap/unwrap) while it holds the loan
+            self.flash_loan_active = true;
+            self.flash_minted = amount;
+            self.release_reentrancy_guard();
+            self.flush_state();
+            
+            let result = build_call::<DefaultEnvironment>()
+                .call(receiver)
+                .call_flags(CallFlags::ALLOW_REENTRY)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22FlashBorrower::on_flash_loan")))
+                        .push_arg(initiator)
+                        .push_arg(amount)
+                        .push_arg(fee)
+                        .push_arg(data),
+                )
+                .returns::<Result<(), FlashBorrowerError>>()
+                .try_invoke();
+            
+            self.reload_state()?;
+            self.flash_loan_active = false;
+            self.flash_minted = 0;
+            self.reentrancy_guard = true;
+            
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return Err(Error::FlashLoanFailed);
+            }
+            
+            // Checks & Effects: Pull repayment through the receiver's allowance
+            let repayment = amount.checked_add(fee).ok_or(Error::Overflow)?;
+            self.spend_allowance(receiver, self.env().account_id(), repayment)?;
+            
+            let receiver_balance = self.balance_of(receiver);
+            if receiver_balance < repayment {
+                return Err(Error::InsufficientBalance);
+            }
+            
+            // Burn the borrowed amount
+            self.total_supply = self.total_supply.checked_sub(amount).ok_or(Error::Overflow)?;
+            self.balances.insert(receiver, &receiver_balance.checked_sub(amount).ok_or(Error::Overflow)?);
+            self.env().emit_event(Transfer {
+                from: Some(receiver),
                 to: None,
                 value: amount,
             });
             
-            self.env().emit_event(Withdrawal {
-                account: caller,
+            // Pay the fee
+            if fee > 0 {
+                self.transfer_helper(receiver, self.fee_recipient, fee, Vec::new())?;
+            }
+            
+            // Security: Supply must be back to its prior value
+            if self.total_supply != supply_before {
+                return Err(Error::InvalidState);
+            }
+            self.validate_invariants()?;
+            
+            self.env().emit_event(FlashLoan {
+                receiver,
+                initiator,
+                amount,
+                fee,
+            });
+            
+            Ok(())
+        }
+
+        /// Lends `amount` of the native LUNES reserve to `receiver` by calling
+        /// its payable `NativeFlashBorrower::on_flash_borrow` hook with the value.
+        ///
+        /// Before the hook returns, the receiver must send back `amount +
+        /// flash_fee(amount)` native LUNES with a plain balance transfer. The
+        /// repaid excess is minted as WLUNES to the fee recipient so the 1:1
+        /// backing holds. The hook cannot call back into WLUNES.
+        #[ink(message)]
+        pub fn flash_borrow_native(&mut self, receiver: AccountId, amount: Balance, data: Vec<u8>) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::FlashBorrowNative)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.flash_borrow_native_internal(receiver, amount, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal native flash loan logic
+        fn flash_borrow_native_internal(&mut self, receiver: AccountId, amount: Balance, data: Vec<u8>) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            self.ensure_no_flash_loan()?;
+            
+            let initiator = self.env().caller();
+            self.enhanced_address_validation(initiator)?;
+            self.enhanced_address_validation(receiver)?;
+            
+            // Checks: Reserve covers the loan, receiver can take the callback
+            let balance_before = self.env().balance();
+            if amount > self.backing_balance() {
+                return Err(Error::InsufficientBalance);
+            }
+            if !self.env().is_contract(&receiver) {
+                return Err(Error::FlashLoanFailed);
+            }
+            
+            let fee = self.flash_fee(amount);
+            let required = balance_before.checked_add(fee).ok_or(Error::Overflow)?;
+            
+            // Interactions: Reentry is not allowed, so the receiver cannot
+            // call back into WLUNES while it holds the reserve
+            let result = build_call::<DefaultEnvironment>()
+                .call(receiver)
+                .transferred_value(amount)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("NativeFlashBorrower::on_flash_borrow")))
+                        .push_arg(initiator)
+                        .push_arg(amount)
+                        .push_arg(fee)
+                        .push_arg(data),
+                )
+                .returns::<Result<(), FlashBorrowerError>>()
+                .try_invoke();
+            
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return Err(Error::FlashLoanFailed);
+            }
+            
+            // Checks: Reserve restored plus fee
+            let balance_after = self.env().balance();
+            if balance_after < required {
+                return Err(Error::InsufficientBalance);
+            }
+            
+            // Effects: Mint the repaid excess to the fee recipient to keep 1:1 backing
+            let excess = balance_after.saturating_sub(balance_before);
+            if excess > 0 {
+                let recipient_balance = self.balance_of(self.fee_recipient);
+                self.total_supply = self.total_supply.checked_add(excess).ok_or(Error::Overflow)?;
+                self.balances.insert(self.fee_recipient, &recipient_balance.checked_add(excess).ok_or(Error::Overflow)?);
+                self.env().emit_event(Transfer {
+                    from: None,
+                    to: Some(self.fee_recipient),
+                    value: excess,
+                });
+            }
+            
+            self.validate_invariants()?;
+            
+            self.env().emit_event(NativeFlashLoan {
+                receiver,
+                initiator,
+                amount,
+                fee: excess,
+            });
+            
+            Ok(())
+        }
+
+        /// Returns the recommended gas limit (`ref_time` and `proof_size`)
+        /// for `operation`.
+        #[ink(message)]
+        pub fn recommended_weight(&self, operation: Operation) -> OperationWeight {
+            operation.recommended_weight()
+        }
+
+        /// Returns the security limits fixed at deployment.
+        #[ink(message)]
+        pub fn security_config(&self) -> SecurityConfig {
+            self.config.clone()
+        }
+
+        /// Returns the native LUNES held above the WLUNES supply.
+        ///
+        /// Surplus comes from `burn` (which keeps the native LUNES) and from
+        /// balance transfers to the contract that did not mint WLUNES.
+        #[ink(message)]
+        pub fn surplus(&self) -> Balance {
+            self.backing_balance().saturating_sub(self.total_supply)
+        }
+
+        /// Sends the surplus native LUNES to the fee recipient fixed at
+        /// construction, restoring exact backing. Callable by anyone.
+        #[ink(message)]
+        pub fn skim(&mut self) -> Result<(), Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Skim)?;
+            
+            // Security: Reentrancy guard
+            self.reentrancy_guard()?;
+            
+            let result = self.skim_internal();
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
+            
+            result
+        }
+
+        /// Internal skim logic
+        fn skim_internal(&mut self) -> Result<(), Error> {
+            self.ensure_no_flash_loan()?;
+            
+            // Checks: Only balance above the supply can leave
+            let amount = self.surplus();
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            
+            let beneficiary = self.fee_recipient;
+            self.env().emit_event(Skim {
+                caller: self.env().caller(),
+                beneficiary,
                 amount,
-                timestamp,
             });
+            
+            // Interactions: Transfer the surplus (last step)
+            self.transfer_native(beneficiary, amount)?;
+            
+            // Security: Supply must still be fully backed
+            self.validate_invariants()
+        }
+
+        /// Returns the maximum amount available for a flash loan.
+        #[ink(message)]
+        pub fn max_flash_loan(&self) -> Balance {
+            // Stays within the total supply ceiling enforced by validate_invariants
+            self.config.max_total_supply.saturating_sub(self.total_supply)
+        }
+
+        /// Returns the WLUNES minted by the flash loan in progress, 0 outside
+        /// of a loan.
+        #[ink(message)]
+        pub fn flash_minted(&self) -> Balance {
+            self.flash_minted
+        }
 
-            // Interactions: Transfer native LUNES tokens to caller (last step)
-            self.env().transfer(caller, amount).map_err(|_| Error::InvalidState)?;
+        /// Returns the fee charged for a flash loan of `amount`.
+        #[ink(message)]
+        pub fn flash_fee(&self, amount: Balance) -> Balance {
+            let bps = Balance::from(self.flash_fee_bps);
+            let denominator = Balance::from(MAX_BPS);
+            // Split to avoid overflow on large amounts
+            let whole = amount.checked_div(denominator).unwrap_or(0).saturating_mul(bps);
+            let remainder = amount.checked_rem(denominator).unwrap_or(0);
+            whole.saturating_add(remainder.saturating_mul(bps).checked_div(denominator).unwrap_or(0))
+        }
+
+        /// Returns the next signature nonce of `owner`.
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or(0)
+        }
+
+        /// Returns the domain separator committed to by signed payloads.
+        ///
+        /// Hash of `(b"WLUNES::permit", chain_id, contract address)`.
+        #[ink(message)]
+        pub fn domain_separator(&self) -> [u8; 32] {
+            self.env()
+                .hash_encoded::<Blake2x256, _>(&(PERMIT_DOMAIN, self.chain_id, self.env().account_id()))
+        }
 
+        /// Rejects signed messages on a deployment without a chain id, whose
+        /// signatures would be valid at the same address on any chain.
+        fn ensure_chain_id(&self) -> Result<(), Error> {
+            if self.chain_id == Hash::default() {
+                return Err(Error::ChainIdNotSet);
+            }
+            Ok(())
+        }
+
+        /// Verifies that `signature` over `message` was produced by `owner`.
+        fn verify_signature(&self, owner: AccountId, message: &[u8; 32], signature: &Signature) -> Result<(), Error> {
+            let signer = match signature {
+                Signature::Sr25519(signature) => {
+                    let public_key: &[u8; 32] = owner.as_ref();
+                    self.env()
+                        .sr25519_verify(signature, message, public_key)
+                        .map_err(|_| Error::InvalidSignature)?;
+                    owner
+                }
+                Signature::Ecdsa(signature) => {
+                    let public_key = self
+                        .env()
+                        .ecdsa_recover(signature, message)
+                        .map_err(|_| Error::InvalidSignature)?;
+                    AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key))
+                }
+            };
+            
+            if signer != owner {
+                return Err(Error::InvalidSignature);
+            }
             Ok(())
         }
+    }
 
+    impl PSP22 for Wlunes {
         /// Returns the total token supply.
         #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
+        fn total_supply(&self) -> Balance {
             self.total_supply
         }
 
         /// Returns the account balance for the specified `owner`.
         #[ink(message)]
-        pub fn balance_of(&self, owner: AccountId) -> Balance {
+        fn balance_of(&self, owner: AccountId) -> Balance {
             self.balances.get(owner).unwrap_or(0)
         }
 
         /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
         #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
             self.allowances.get((owner, spender)).unwrap_or(0)
         }
 
         /// Transfers `value` amount of tokens from the caller's account to account `to`.
         #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), Error> {
+        fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Transfer)?;
+            
             let from = self.env().caller();
             
             // Enhanced security validations
-            self.enhanced_address_validation(from)?;
-            self.enhanced_address_validation(to)?;
+            self.enhanced_address_validation(from)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroSenderAddress))?;
+            self.enhanced_address_validation(to)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroRecipientAddress))?;
             self.validate_amount_limits(value)?;
+            self.validate_rate_limiting(from)?;
+            
+            // Security: Reentrancy guard (receiver hook is an external call)
+            self.reentrancy_guard()?;
+            
+            let result = self.transfer_helper(from, to, value, data);
+            
+            // Always release reentrancy guard
+            self.release_reentrancy_guard();
             
-            self.transfer_helper(from, to, value)
+            result.map_err(PSP22Error::from)
         }
 
         /// Transfers `value` tokens on behalf of `from` to the account `to`.
         #[ink(message)]
-        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), Error> {
+        fn transfer_from(&mut self, from: AccountId, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::TransferFrom)?;
+            
             let caller = self.env().caller();
             
             // Enhanced security validations
             self.enhanced_address_validation(caller)?;
-            self.enhanced_address_validation(from)?;
-            self.enhanced_address_validation(to)?;
+            self.enhanced_address_validation(from)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroSenderAddress))?;
+            self.enhanced_address_validation(to)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroRecipientAddress))?;
             self.validate_amount_limits(value)?;
+            self.validate_rate_limiting(caller)?;
             
-            let allowance = self.allowance(from, caller);
+            // Update allowance
+            self.spend_allowance(from, caller, value)?;
             
-            if allowance < value {
-                return Err(Error::InsufficientAllowance);
+            // Security: Reentrancy guard (receiver hook is an external call),
+            // already held if the `deposit_and_call` target pulls the deposit
+            let deposit_call = self.deposit_call == Some((from, caller));
+            if !deposit_call {
+                self.reentrancy_guard()?;
             }
             
-            // Update allowance
-            self.allowances.insert((from, caller), &allowance.checked_sub(value).ok_or(Error::Overflow)?);
-            
             // Transfer tokens
-            self.transfer_helper(from, to, value)
+            let result = self.transfer_helper(from, to, value, data);
+            
+            // Release the guard only if this call took it
+            if !deposit_call {
+                self.release_reentrancy_guard();
+            }
+            
+            result.map_err(PSP22Error::from)
         }
 
         /// Sets the allowance for `spender` to `value`.
         #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), Error> {
-            let owner = self.env().caller();
-            
-            // Security: Validate addresses
-            self.ensure_not_zero_address(owner)?;
-            self.ensure_not_zero_address(spender)?;
-            
-            // Security: Prevent self-approval with non-zero value (potential attack vector)
-            if value > 0 && owner == spender {
-                return Err(Error::InvalidState);
-            }
-            
-            // Effects: Update allowance
-            self.allowances.insert((owner, spender), &value);
-            
-            // Emit approval event
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value,
-            });
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Approve)?;
             
-            Ok(())
+            let owner = self.env().caller();
+            self.approve_helper(owner, spender, value).map_err(PSP22Error::from)
         }
 
-        /// Helper function for transferring tokens.
-        fn transfer_helper(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
-            if value == 0 {
-                return Err(Error::ZeroAmount);
-            }
-            
-            // Gas optimization: Early return for self-transfer
-            if from == to {
-                return Ok(());
-            }
-            
-            // Gas optimization: Single storage reads
-            let from_balance = self.balance_of(from);
-            let to_balance = self.balance_of(to);
+        /// Atomically increases the allowance granted to `spender` by `delta_value`.
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::IncreaseAllowance)?;
             
-            // Checks: Validate sufficient balance
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
+            let owner = self.env().caller();
             
-            // Security: Check for overflow/underflow before state changes
-            let new_from_balance = from_balance.checked_sub(value).ok_or(Error::Overflow)?;
-            let new_to_balance = to_balance.checked_add(value).ok_or(Error::Overflow)?;
+            // Security: Checked arithmetic on the current allowance
+            let value = self.allowance(owner, spender).checked_add(delta_value).ok_or(Error::Overflow)?;
             
-            // Effects: Update balances
-            self.balances.insert(from, &new_from_balance);
-            self.balances.insert(to, &new_to_balance);
+            self.approve_helper(owner, spender, value).map_err(PSP22Error::from)
+        }
+
+        /// Atomically decreases the allowance granted to `spender` by `delta_value`.
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::DecreaseAllowance)?;
             
-            // Security: Validate invariants (total supply unchanged)
-            self.validate_invariants()?;
+            let owner = self.env().caller();
             
-            // Emit transfer event
-            self.env().emit_event(Transfer {
-                from: Some(from),
-                to: Some(to),
-                value,
-            });
+            // Checks: Allowance cannot go below zero
+            let value = self
+                .allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
             
-            Ok(())
+            self.approve_helper(owner, spender, value).map_err(PSP22Error::from)
         }
+    }
 
+    impl PSP22Metadata for Wlunes {
         /// Returns the token name.
         #[ink(message)]
-        pub fn token_name(&self) -> Option<String> {
+        fn token_name(&self) -> Option<String> {
             Some("Wrapped Lunes".into())
         }
 
         /// Returns the token symbol.
         #[ink(message)]
-        pub fn token_symbol(&self) -> Option<String> {
+        fn token_symbol(&self) -> Option<String> {
             Some("WLUNES".into())
         }
 
         /// Returns the token decimals.
         #[ink(message)]
-        pub fn token_decimals(&self) -> u8 {
+        fn token_decimals(&self) -> u8 {
             8
         }
+    }
 
+    impl PSP22Burnable for Wlunes {
         /// Burns `value` tokens from the `from` account.
         /// This is used internally by the withdraw function.
         #[ink(message)]
-        pub fn burn(&mut self, from: AccountId, value: Balance) -> Result<(), Error> {
+        fn burn(&mut self, from: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            // Security: Reject gas limits too low to complete the call
+            self.validate_gas_limit(Operation::Burn)?;
+            
             if value == 0 {
-                return Err(Error::ZeroAmount);
+                return Err(Error::ZeroAmount.into());
             }
 
             let caller = self.env().caller();
             
             // Security: Validate addresses
-            self.ensure_not_zero_address(from)?;
+            self.ensure_not_zero_address(from)
+                .map_err(|e| e.into_psp22(PSP22Error::ZeroSenderAddress))?;
             self.ensure_not_zero_address(caller)?;
             
             // Gas optimization: Single storage reads
@@ -675,18 +2515,12 @@ pub mod wlunes {
             
             // Checks: Validate sufficient balance
             if from_balance < value {
-                return Err(Error::InsufficientBalance);
+                return Err(PSP22Error::InsufficientBalance);
             }
             
             // Handle allowance if burning from different account
             if from != caller {
-                let allowance = self.allowance(from, caller);
-                if allowance < value {
-                    return Err(Error::InsufficientAllowance);
-                }
-                // Security: Check for underflow before updating allowance
-                let new_allowance = allowance.checked_sub(value).ok_or(Error::Overflow)?;
-                self.allowances.insert((from, caller), &new_allowance);
+                self.spend_allowance(from, caller, value)?;
             }
             
             // Security: Check for underflow before state changes
@@ -735,6 +2569,38 @@ pub mod wlunes {
                 test::callee::<DefaultEnvironment>()
             }
 
+            fn skip_cooldown() {
+                let now = ink::env::block_timestamp::<DefaultEnvironment>();
+                test::set_block_timestamp::<DefaultEnvironment>(now + SecurityConfig::default().transaction_cooldown);
+            }
+
+            thread_local! {
+                /// Recipients whose native transfers fail in `transfer_native`
+                static REJECTED_RECIPIENTS: core::cell::RefCell<Vec<AccountId>> =
+                    const { core::cell::RefCell::new(Vec::new()) };
+                /// `ref_time` reported by `Wlunes::gas_left`, unlimited by default
+                static GAS_LEFT: core::cell::Cell<u64> = const { core::cell::Cell::new(u64::MAX) };
+            }
+
+            /// Sets the `ref_time` left for the following calls
+            fn set_gas_left(ref_time: u64) {
+                GAS_LEFT.with(|gas_left| gas_left.set(ref_time));
+            }
+
+            pub(super) fn gas_left() -> u64 {
+                GAS_LEFT.with(|gas_left| gas_left.get())
+            }
+
+            /// Makes native transfers to `recipient` fail, which the off-chain
+            /// environment cannot do on its own
+            fn reject_native_transfers_to(recipient: AccountId) {
+                REJECTED_RECIPIENTS.with(|rejected| rejected.borrow_mut().push(recipient));
+            }
+
+            pub(super) fn rejects_native_transfers_to(recipient: AccountId) -> bool {
+                REJECTED_RECIPIENTS.with(|rejected| rejected.borrow().contains(&recipient))
+            }
+
             #[ink::test]
             fn constructor_works() {
                 let wlunes = Wlunes::new();
@@ -784,6 +2650,7 @@ pub mod wlunes {
                 set_balance(contract_id(), 10000000);
                 
                 // Now withdraw
+                skip_cooldown();
                 assert_eq!(wlunes.withdraw(50), Ok(()));
                 assert_eq!(wlunes.total_supply(), 50);
                 assert_eq!(wlunes.balance_of(accounts.alice), 50);
@@ -801,6 +2668,7 @@ pub mod wlunes {
                 assert_eq!(wlunes.deposit(), Ok(()));
                 
                 // Transfer to Bob
+                skip_cooldown();
                 assert_eq!(wlunes.transfer(accounts.bob, 30, vec![]), Ok(()));
                 assert_eq!(wlunes.balance_of(accounts.alice), 70);
                 assert_eq!(wlunes.balance_of(accounts.bob), 30);
@@ -864,10 +2732,296 @@ pub mod wlunes {
                 
                 // After withdrawal
                 set_balance(contract_id(), 10000000);
+                skip_cooldown();
                 assert_eq!(wlunes.withdraw(30), Ok(()));
                 assert_eq!(wlunes.total_supply(), 70);
             }
 
+            #[ink::test]
+            fn invariant_requires_backing_net_of_existential_deposit() {
+                let accounts = default_accounts();
+                test::set_callee::<DefaultEnvironment>(accounts.django);
+                let mut wlunes = Wlunes::new();
+                let existential_deposit = ink::env::minimum_balance::<DefaultEnvironment>();
+                
+                // Contract keeps its existential deposit plus the deposited LUNES
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                set_balance(contract_id(), existential_deposit + 100);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(wlunes.deposit(), Ok(()));
+                assert_eq!(wlunes.validate_invariants(), Ok(()));
+                
+                // Excess backing is allowed
+                set_balance(contract_id(), existential_deposit + 150);
+                assert_eq!(wlunes.validate_invariants(), Ok(()));
+                assert_eq!(wlunes.surplus(), 50);
+                
+                // The existential deposit does not count as backing
+                set_balance(contract_id(), existential_deposit + 99);
+                assert_eq!(wlunes.validate_invariants(), Err(Error::InvalidState));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                skip_cooldown();
+                assert_eq!(
+                    wlunes.transfer(accounts.bob, 10, vec![]),
+                    Err(PSP22Error::Custom("InvalidState".into()))
+                );
+            }
+
+            #[ink::test]
+            fn last_holder_can_withdraw_everything() {
+                let accounts = default_accounts();
+                test::set_callee::<DefaultEnvironment>(accounts.django);
+                let mut wlunes = Wlunes::new();
+                let existential_deposit = ink::env::minimum_balance::<DefaultEnvironment>();
+                
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                set_balance(contract_id(), existential_deposit + 100);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(wlunes.deposit(), Ok(()));
+                
+                skip_cooldown();
+                assert_eq!(wlunes.withdraw(100), Ok(()));
+                assert_eq!(wlunes.total_supply(), 0);
+                assert_eq!(test::get_account_balance::<DefaultEnvironment>(contract_id()), Ok(existential_deposit));
+                assert_eq!(wlunes.validate_invariants(), Ok(()));
+            }
+
+            #[ink::test]
+            fn native_transfer_errors_are_distinct() {
+                let wlunes = Wlunes::new();
+                let existential_deposit = ink::env::minimum_balance::<DefaultEnvironment>();
+                
+                assert_eq!(
+                    wlunes.native_transfer_error(existential_deposit - 1),
+                    Error::NativeTransferBelowExistentialDeposit
+                );
+                assert_eq!(wlunes.native_transfer_error(existential_deposit), Error::NativeTransferFailed);
+            }
+
+            #[ink::test]
+            fn weight_check_rejects_only_insufficient_gas() {
+                let mut wlunes = Wlunes::new();
+                let minimum = Operation::Withdraw.min_ref_time();
+                
+                // An accurate limit is never rejected
+                assert_eq!(wlunes.check_weight(Operation::Withdraw, Operation::Withdraw.recommended_weight().ref_time), Ok(()));
+                assert_eq!(wlunes.check_weight(Operation::Withdraw, minimum), Ok(()));
+                assert_eq!(wlunes.check_weight(Operation::Withdraw, minimum - 1), Err(Error::GasLimitExceeded));
+                
+                // Alert mode reports the shortfall without blocking
+                wlunes.config.gas_limit_mode = CheckMode::Alert;
+                assert_eq!(wlunes.check_weight(Operation::Withdraw, minimum - 1), Ok(()));
+            }
+
+            #[ink::test]
+            fn messages_reject_insufficient_gas_before_any_effect() {
+                let mut wlunes = Wlunes::new();
+                let accounts = default_accounts();
+                
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                set_gas_left(Operation::Deposit.min_ref_time() - 1);
+                assert_eq!(wlunes.deposit(), Err(Error::GasLimitExceeded));
+                assert_eq!(wlunes.total_supply(), 0);
+                
+                set_gas_left(Operation::Deposit.min_ref_time());
+                assert_eq!(wlunes.deposit(), Ok(()));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                
+                // PSP22 messages report the code through `Custom`
+                skip_cooldown();
+                set_gas_left(Operation::Transfer.min_ref_time() - 1);
+                assert_eq!(
+                    wlunes.transfer(accounts.bob, 10, vec![]),
+                    Err(PSP22Error::Custom("GasLimitExceeded".into()))
+                );
+                set_gas_left(Operation::Withdraw.min_ref_time() - 1);
+                assert_eq!(wlunes.withdraw(10), Err(Error::GasLimitExceeded));
+                assert_eq!(wlunes.balance_of(accounts.alice), 100);
+                
+                set_gas_left(Operation::Transfer.recommended_weight().ref_time);
+                assert_eq!(wlunes.transfer(accounts.bob, 10, vec![]), Ok(()));
+            }
+
+            #[ink::test]
+            fn pending_withdrawal_can_be_claimed() {
+                let accounts = default_accounts();
+                test::set_callee::<DefaultEnvironment>(accounts.django);
+                let mut wlunes = Wlunes::new();
+                let existential_deposit = ink::env::minimum_balance::<DefaultEnvironment>();
+                
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                set_balance(accounts.bob, 0);
+                set_balance(contract_id(), existential_deposit + 100);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(wlunes.deposit(), Ok(()));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                
+                // The push to Bob fails, so Alice's opted-in unwrap is deferred
+                assert_eq!(wlunes.set_withdrawal_fallback(true), Ok(()));
+                reject_native_transfers_to(accounts.bob);
+                skip_cooldown();
+                assert_eq!(wlunes.withdraw_to(accounts.bob, 40), Ok(()));
+                assert_eq!(wlunes.balance_of(accounts.alice), 60);
+                assert_eq!(wlunes.total_supply(), 60);
+                assert_eq!(wlunes.pending_withdrawal(accounts.alice), 40);
+                assert_eq!(wlunes.total_pending, 40);
+                assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.bob), Ok(0));
+                
+                // Pending claims are not surplus and stay backed
+                assert_eq!(wlunes.surplus(), 0);
+                assert_eq!(wlunes.validate_invariants(), Ok(()));
+                
+                // The claim pays the owner, not the failed recipient
+                assert_eq!(wlunes.claim_withdrawal(), Ok(()));
+                assert_eq!(wlunes.pending_withdrawal(accounts.alice), 0);
+                assert_eq!(wlunes.total_pending, 0);
+                assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.alice), Ok(10000040));
+                assert_eq!(
+                    test::get_account_balance::<DefaultEnvironment>(contract_id()),
+                    Ok(existential_deposit + 60)
+                );
+                assert_eq!(wlunes.claim_withdrawal(), Err(Error::ZeroAmount));
+                
+                // Without the fallback the failed push is reported
+                assert_eq!(wlunes.set_withdrawal_fallback(false), Ok(()));
+                skip_cooldown();
+                assert_eq!(
+                    wlunes.withdraw_to(accounts.bob, 10),
+                    Err(Error::NativeTransferBelowExistentialDeposit)
+                );
+            }
+
+            #[ink::test]
+            fn psp22_errors_work() {
+                let mut wlunes = Wlunes::new();
+                let accounts = default_accounts();
+                let zero = AccountId::from([0u8; 32]);
+                
+                set_next_caller(accounts.alice);
+                assert_eq!(wlunes.transfer(zero, 10, vec![]), Err(PSP22Error::ZeroRecipientAddress));
+                assert_eq!(
+                    wlunes.transfer_from(zero, accounts.bob, 10, vec![]),
+                    Err(PSP22Error::ZeroSenderAddress)
+                );
+                
+                // WLUNES-specific errors map to stable custom codes
+                assert_eq!(
+                    PSP22Error::from(Error::ReentrancyDetected),
+                    PSP22Error::Custom("ReentrancyDetected".into())
+                );
+                assert_eq!(PSP22Error::from(Error::InsufficientBalance), PSP22Error::InsufficientBalance);
+            }
+
+            #[ink::test]
+            fn wrap_and_unwrap_blocked_during_flash_loan() {
+                let accounts = default_accounts();
+                test::set_callee::<DefaultEnvironment>(accounts.django);
+                let mut wlunes = Wlunes::new();
+                let existential_deposit = ink::env::minimum_balance::<DefaultEnvironment>();
+                
+                // The reserve backs exactly the deposited supply
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                set_balance(contract_id(), existential_deposit + 100);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(wlunes.deposit(), Ok(()));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                
+                // Flash-mint 1000 unbacked WLUNES to the borrower, as flash_loan does
+                wlunes.total_supply += 1000;
+                wlunes.balances.insert(accounts.charlie, &1000);
+                wlunes.flash_loan_active = true;
+                wlunes.flash_minted = 1000;
+                
+                // State as seen by a reentrant call from the flash borrower
+                wlunes.flush_state();
+                let mut reentrant: Wlunes = ink::env::get_contract_storage(
+                    &<Wlunes as ink::storage::traits::StorageKey>::KEY,
+                ).unwrap().unwrap();
+                
+                set_next_caller(accounts.charlie);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(reentrant.deposit(), Err(Error::ReentrancyDetected));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                assert_eq!(reentrant.withdraw(10), Err(Error::ReentrancyDetected));
+                assert_eq!(reentrant.flash_loan(accounts.bob, 10, vec![]), Err(Error::ReentrancyDetected));
+                
+                // The borrower can move the borrowed WLUNES
+                assert_eq!(reentrant.transfer(accounts.bob, 600, vec![]), Ok(()));
+                skip_cooldown();
+                assert_eq!(reentrant.batch_transfer(vec![(accounts.eve, 400)], vec![]), Ok(()));
+                
+                reentrant.flush_state();
+                assert_eq!(wlunes.reload_state(), Ok(()));
+                assert_eq!(wlunes.balance_of(accounts.bob), 600);
+                assert_eq!(wlunes.balance_of(accounts.eve), 400);
+                
+                // Once the loan is over the minted amount must be backed again
+                wlunes.flash_minted = 0;
+                assert_eq!(wlunes.validate_invariants(), Err(Error::InvalidState));
+            }
+
+            #[ink::test]
+            fn deposit_call_target_may_only_reenter_transfer_from() {
+                let accounts = default_accounts();
+                test::set_callee::<DefaultEnvironment>(accounts.django);
+                let mut wlunes = Wlunes::new();
+                
+                set_next_caller(accounts.alice);
+                set_balance(accounts.alice, 10000000);
+                set_balance(contract_id(), 10000000);
+                test::set_value_transferred::<DefaultEnvironment>(100);
+                assert_eq!(wlunes.deposit(), Ok(()));
+                test::set_value_transferred::<DefaultEnvironment>(0);
+                assert_eq!(wlunes.approve(accounts.charlie, 100), Ok(()));
+                assert_eq!(wlunes.approve(accounts.eve, 100), Ok(()));
+                
+                // State as seen by the target (charlie) during deposit_and_call
+                wlunes.reentrancy_guard = true;
+                wlunes.deposit_call = Some((accounts.alice, accounts.charlie));
+                wlunes.flush_state();
+                let mut reentrant: Wlunes = ink::env::get_contract_storage(
+                    &<Wlunes as ink::storage::traits::StorageKey>::KEY,
+                ).unwrap().unwrap();
+                
+                set_next_caller(accounts.charlie);
+                assert_eq!(reentrant.transfer_from(accounts.alice, accounts.bob, 60, vec![]), Ok(()));
+                assert_eq!(reentrant.withdraw(10), Err(Error::ReentrancyDetected));
+                assert_eq!(reentrant.skim(), Err(Error::ReentrancyDetected));
+                assert_eq!(reentrant.claim_withdrawal(), Err(Error::ReentrancyDetected));
+                
+                // Other spenders stay locked out, and the guard is still held
+                set_next_caller(accounts.eve);
+                assert_eq!(
+                    reentrant.transfer_from(accounts.alice, accounts.bob, 10, vec![]),
+                    Err(PSP22Error::Custom("ReentrancyDetected".into()))
+                );
+                assert!(reentrant.reentrancy_guard);
+                assert_eq!(reentrant.balance_of(accounts.bob), 60);
+            }
+
+            #[ink::test]
+            fn forwarded_call_data_is_raw() {
+                use scale::{Decode, Encode};
+                
+                // Calldata is appended without a length prefix
+                let input = ExecutionInput::new(Selector::new([1, 2, 3, 4])).push_arg(RawCallData(vec![5, 6]));
+                assert_eq!(input.encode(), vec![1, 2, 3, 4, 5, 6]);
+                
+                // `()` and `Ok(..)` outputs succeed, `Err(..)` does not
+                let unit = RawCallOutput::decode(&mut &[][..]).unwrap();
+                let ok = RawCallOutput::decode(&mut &Ok::<u32, ()>(7).encode()[..]).unwrap();
+                let err = RawCallOutput::decode(&mut &Err::<(), u8>(1).encode()[..]).unwrap();
+                assert!(unit.succeeded());
+                assert!(ok.succeeded());
+                assert!(!err.succeeded());
+            }
+
             #[ink::test]
             fn error_cases_work() {
                 let mut wlunes = Wlunes::new();
@@ -879,17 +3033,18 @@ pub mod wlunes {
                 test::set_value_transferred::<DefaultEnvironment>(0);
                 assert_eq!(wlunes.deposit(), Err(Error::ZeroAmount));
                 assert_eq!(wlunes.withdraw(0), Err(Error::ZeroAmount));
-                assert_eq!(wlunes.transfer(accounts.bob, 0, vec![]), Err(Error::ZeroAmount));
+                assert_eq!(wlunes.burn(accounts.alice, 0), Err(PSP22Error::Custom("ZeroAmount".into())));
                 
                 // Insufficient balance errors
                 assert_eq!(wlunes.withdraw(100), Err(Error::InsufficientBalance));
-                assert_eq!(wlunes.transfer(accounts.bob, 100, vec![]), Err(Error::InsufficientBalance));
+                skip_cooldown();
+                assert_eq!(wlunes.transfer(accounts.bob, 100, vec![]), Err(PSP22Error::InsufficientBalance));
                 
                 // Insufficient allowance error
                 set_next_caller(accounts.bob);
                 assert_eq!(
                     wlunes.transfer_from(accounts.alice, accounts.charlie, 100, vec![]),
-                    Err(Error::InsufficientAllowance)
+                    Err(PSP22Error::InsufficientAllowance)
                 );
             }
         }
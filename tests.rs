@@ -1,331 +1,1511 @@
-#[cfg(test)]
-mod tests {
-    use super::wlunes::*;
-    use ink::env::{
-        test::{self, DefaultAccounts, EmittedEvent},
-        DefaultEnvironment,
+//! Integration-style unit tests for the WLUNES contract.
+
+use crate::traits::*;
+use crate::wlunes::*;
+use ink::env::{
+    test::{self, DefaultAccounts, EmittedEvent},
+    DefaultEnvironment,
+};
+use ink::primitives::{AccountId, Hash};
+use scale::Decode;
+
+fn default_accounts() -> DefaultAccounts<DefaultEnvironment> {
+    test::default_accounts::<DefaultEnvironment>()
+}
+
+fn set_next_caller(caller: ink::primitives::AccountId) {
+    test::set_caller::<DefaultEnvironment>(caller);
+}
+
+fn set_balance(account: ink::primitives::AccountId, balance: u128) {
+    test::set_account_balance::<DefaultEnvironment>(account, balance);
+}
+
+fn get_balance(account: ink::primitives::AccountId) -> u128 {
+    test::get_account_balance::<DefaultEnvironment>(account).unwrap_or(0)
+}
+
+fn contract_id() -> ink::primitives::AccountId {
+    test::callee::<DefaultEnvironment>()
+}
+
+/// Moves the clock past the default per-account cooldown.
+fn skip_cooldown() {
+    let now = ink::env::block_timestamp::<DefaultEnvironment>();
+    test::set_block_timestamp::<DefaultEnvironment>(now + SecurityConfig::default().transaction_cooldown);
+}
+
+/// Decodes the recorded events of type `E`, in emission order.
+fn decoded_events<E: ink::env::Event + Decode>() -> Vec<E> {
+    let signature = E::SIGNATURE_TOPIC.map(|topic| topic.to_vec());
+    test::recorded_events()
+        .filter(|event: &EmittedEvent| event.topics.first() == signature.as_ref())
+        .map(|event| E::decode(&mut &event.data[..]).expect("event data decodes"))
+        .collect()
+}
+
+/// Deploys WLUNES bound to a test chain id, which signed messages require.
+fn new_signing_wlunes() -> Wlunes {
+    Wlunes::new_for_chain(Hash::from([0x42; 32]))
+}
+
+/// Deploys WLUNES with a rate limiter of `burst` operations per `window`
+/// and no cooldown, so the bucket is tested on its own.
+fn new_rate_limited_wlunes(window: u64, burst: u32) -> Wlunes {
+    let config = SecurityConfig {
+        transaction_cooldown: 0,
+        rate_limit_window: window,
+        rate_limit_burst: burst,
+        ..SecurityConfig::default()
     };
+    Wlunes::new_with_config(Default::default(), 0, default_accounts().alice, config)
+}
 
-    type Event = <Wlunes as ink::reflect::ContractEventBase>::Type;
+/// Blake2-256 hash of the SCALE-encoded permit payload.
+fn permit_message(
+    wlunes: &Wlunes,
+    owner: AccountId,
+    spender: AccountId,
+    value: u128,
+    deadline: u64,
+) -> [u8; 32] {
+    let payload = (wlunes.domain_separator(), owner, spender, value, wlunes.nonces(owner), deadline);
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&payload, &mut output);
+    output
+}
 
-    fn default_accounts() -> DefaultAccounts<DefaultEnvironment> {
-        test::default_accounts::<DefaultEnvironment>()
-    }
+/// Signs `intent` for `execute_signed` with `keypair`.
+fn sign_intent(wlunes: &Wlunes, keypair: &schnorrkel::Keypair, intent: &SignedIntent) -> Signature {
+    let payload = (wlunes.domain_separator(), b"WLUNES::execute_signed".as_slice(), intent);
+    let mut message = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(&payload, &mut message);
+    Signature::Sr25519(keypair.sign_simple(b"substrate", &message).to_bytes())
+}
 
-    fn set_next_caller(caller: ink::primitives::AccountId) {
-        test::set_caller::<DefaultEnvironment>(caller);
-    }
+fn sr25519_keypair(seed: u8) -> schnorrkel::Keypair {
+    schnorrkel::MiniSecretKey::from_bytes(&[seed; 32])
+        .unwrap()
+        .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519)
+}
 
-    fn set_balance(account: ink::primitives::AccountId, balance: u128) {
-        test::set_account_balance::<DefaultEnvironment>(account, balance);
-    }
+fn assert_approval(event: &Approval, owner: AccountId, spender: AccountId, value: u128) {
+    assert_eq!(event.owner, owner);
+    assert_eq!(event.spender, spender);
+    assert_eq!(event.value, value);
+}
 
-    fn get_balance(account: ink::primitives::AccountId) -> u128 {
-        test::get_account_balance::<DefaultEnvironment>(account).unwrap_or(0)
-    }
+#[ink::test]
+fn constructor_works() {
+    let wlunes = Wlunes::new();
+    assert_eq!(wlunes.total_supply(), 0);
+    assert_eq!(wlunes.token_name(), Some("Wrapped Lunes".into()));
+    assert_eq!(wlunes.token_symbol(), Some("WLUNES".into()));
+    assert_eq!(wlunes.token_decimals(), 8);
+}
 
-    fn contract_id() -> ink::primitives::AccountId {
-        test::callee::<DefaultEnvironment>()
-    }
+#[ink::test]
+fn deposit_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Set caller and give them some native balance
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    
+    // Set transferred value to simulate payable call
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    
+    // Deposit should work
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.total_supply(), 100);
+    assert_eq!(wlunes.balance_of(accounts.alice), 100);
+}
 
-    #[ink::test]
-    fn constructor_works() {
-        let wlunes = Wlunes::new();
-        assert_eq!(wlunes.total_supply(), 0);
-        assert_eq!(wlunes.token_name(), Some("Wrapped Lunes".into()));
-        assert_eq!(wlunes.token_symbol(), Some("WLUNES".into()));
-        assert_eq!(wlunes.token_decimals(), 8);
-    }
+#[ink::test]
+fn deposit_zero_amount_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    assert_eq!(wlunes.deposit(), Err(Error::ZeroAmount));
+}
 
-    #[ink::test]
-    fn deposit_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Set caller and give them some native balance
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        
-        // Set transferred value to simulate payable call
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        
-        // Deposit should work
-        assert_eq!(wlunes.deposit(), Ok(()));
-        assert_eq!(wlunes.total_supply(), 100);
-        assert_eq!(wlunes.balance_of(accounts.alice), 100);
-    }
+#[ink::test]
+fn deposit_for_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Alice pays, Bob receives the minted WLUNES
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    
+    assert_eq!(wlunes.deposit_for(accounts.bob, 100), Ok(()));
+    assert_eq!(wlunes.total_supply(), 100);
+    assert_eq!(wlunes.balance_of(accounts.alice), 0);
+    assert_eq!(wlunes.balance_of(accounts.bob), 100);
+    
+    // Declared amount must match the transferred value
+    assert_eq!(wlunes.deposit_for(accounts.bob, 50), Err(Error::AmountMismatch));
+    assert_eq!(wlunes.total_supply(), 100);
+}
 
-    #[ink::test]
-    fn deposit_zero_amount_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        test::set_value_transferred::<DefaultEnvironment>(0);
-        
-        assert_eq!(wlunes.deposit(), Err(Error::ZeroAmount));
-    }
+#[ink::test]
+fn withdraw_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // First deposit some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Set contract balance for withdrawal
+    set_balance(contract_id(), 10000000);
+    
+    // Now withdraw
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw(50), Ok(()));
+    assert_eq!(wlunes.total_supply(), 50);
+    assert_eq!(wlunes.balance_of(accounts.alice), 50);
+}
 
-    #[ink::test]
-    fn withdraw_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // First deposit some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Set contract balance for withdrawal
-        set_balance(contract_id(), 100);
-        
-        // Now withdraw
-        assert_eq!(wlunes.withdraw(50), Ok(()));
-        assert_eq!(wlunes.total_supply(), 50);
-        assert_eq!(wlunes.balance_of(accounts.alice), 50);
-    }
+#[ink::test]
+fn withdraw_insufficient_balance_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.withdraw(100), Err(Error::InsufficientBalance));
+}
 
-    #[ink::test]
-    fn withdraw_insufficient_balance_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.withdraw(100), Err(Error::InsufficientBalance));
-    }
+#[ink::test]
+fn withdraw_zero_amount_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.withdraw(0), Err(Error::ZeroAmount));
+}
 
-    #[ink::test]
-    fn withdraw_zero_amount_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.withdraw(0), Err(Error::ZeroAmount));
-    }
+#[ink::test]
+fn withdraw_to_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    set_balance(contract_id(), 10000000);
+    set_balance(accounts.charlie, 0);
+    
+    // Alice unwraps to Charlie's native account
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw_to(accounts.charlie, 40), Ok(()));
+    assert_eq!(wlunes.total_supply(), 60);
+    assert_eq!(wlunes.balance_of(accounts.alice), 60);
+    assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.charlie), Ok(40));
+}
 
-    #[ink::test]
-    fn transfer_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Give Alice some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Transfer to Bob
-        assert_eq!(wlunes.transfer(accounts.bob, 30, vec![]), Ok(()));
-        assert_eq!(wlunes.balance_of(accounts.alice), 70);
-        assert_eq!(wlunes.balance_of(accounts.bob), 30);
-    }
+#[ink::test]
+fn withdraw_from_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    set_balance(contract_id(), 10000000);
+    set_balance(accounts.charlie, 0);
+    
+    // Bob unwraps Alice's tokens to Charlie using his allowance
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.withdraw_from(accounts.alice, accounts.charlie, 30), Ok(()));
+    assert_eq!(wlunes.total_supply(), 70);
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 20);
+    assert_eq!(test::get_account_balance::<DefaultEnvironment>(accounts.charlie), Ok(30));
+    
+    // Remaining allowance is enforced
+    assert_eq!(
+        wlunes.withdraw_from(accounts.alice, accounts.charlie, 30),
+        Err(Error::InsufficientAllowance)
+    );
+}
 
-    #[ink::test]
-    fn transfer_insufficient_balance_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.transfer(accounts.bob, 100, vec![]), Err(Error::InsufficientBalance));
-    }
+#[ink::test]
+fn transfer_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Give Alice some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Transfer to Bob
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.bob, 30, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.balance_of(accounts.bob), 30);
+}
 
-    #[ink::test]
-    fn transfer_zero_amount_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.transfer(accounts.bob, 0, vec![]), Err(Error::ZeroAmount));
-    }
+#[ink::test]
+fn transfer_insufficient_balance_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.transfer(accounts.bob, 100, vec![]), Err(PSP22Error::InsufficientBalance));
+}
 
-    #[ink::test]
-    fn approve_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.approve(accounts.bob, 100), Ok(()));
-        assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 100);
-    }
+#[ink::test]
+fn transfer_zero_amount_succeeds() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // PSP22: zero-value transfers succeed even without a balance
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.transfer(accounts.bob, 0, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 0);
+    assert_eq!(wlunes.balance_of(accounts.bob), 0);
+    
+    let transfers = decoded_events::<Transfer>();
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].from, Some(accounts.alice));
+    assert_eq!(transfers[0].to, Some(accounts.bob));
+    assert_eq!(transfers[0].value, 0);
+}
 
-    #[ink::test]
-    fn transfer_from_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Give Alice some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Alice approves Bob to spend 50 tokens
-        assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
-        
-        // Bob transfers from Alice to Charlie
-        set_next_caller(accounts.bob);
-        assert_eq!(wlunes.transfer_from(accounts.alice, accounts.charlie, 30, vec![]), Ok(()));
-        
-        assert_eq!(wlunes.balance_of(accounts.alice), 70);
-        assert_eq!(wlunes.balance_of(accounts.charlie), 30);
-        assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 20);
-    }
+#[ink::test]
+fn self_transfer_succeeds() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.alice, 60, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 100);
+    
+    let transfers = decoded_events::<Transfer>();
+    let last = transfers.last().unwrap();
+    assert_eq!(last.from, Some(accounts.alice));
+    assert_eq!(last.to, Some(accounts.alice));
+    assert_eq!(last.value, 60);
+    
+    // Self transfers still require a sufficient balance
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.alice, 101, vec![]), Err(PSP22Error::InsufficientBalance));
+}
 
-    #[ink::test]
-    fn transfer_from_insufficient_allowance_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.bob);
-        assert_eq!(
-            wlunes.transfer_from(accounts.alice, accounts.charlie, 100, vec![]),
-            Err(Error::InsufficientAllowance)
-        );
-    }
+#[ink::test]
+fn approve_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.approve(accounts.bob, 100), Ok(()));
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 100);
+}
 
-    #[ink::test]
-    fn burn_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Give Alice some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Alice burns her own tokens
-        assert_eq!(wlunes.burn(accounts.alice, 30), Ok(()));
-        assert_eq!(wlunes.balance_of(accounts.alice), 70);
-        assert_eq!(wlunes.total_supply(), 70);
-    }
+#[ink::test]
+fn increase_and_decrease_allowance_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.increase_allowance(accounts.bob, 100), Ok(()));
+    assert_eq!(wlunes.increase_allowance(accounts.bob, 50), Ok(()));
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 150);
+    
+    assert_eq!(wlunes.decrease_allowance(accounts.bob, 120), Ok(()));
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 30);
+    
+    // Cannot decrease below zero or overflow
+    assert_eq!(
+        wlunes.decrease_allowance(accounts.bob, 31),
+        Err(PSP22Error::InsufficientAllowance)
+    );
+    assert_eq!(
+        wlunes.increase_allowance(accounts.bob, Balance::MAX),
+        Err(PSP22Error::Custom("Overflow".into()))
+    );
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 30);
+    
+    // Every change emits Approval
+    assert_eq!(test::recorded_events().count(), 3);
+}
 
-    #[ink::test]
-    fn burn_with_allowance_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Give Alice some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Alice approves Bob to burn her tokens
-        assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
-        
-        // Bob burns Alice's tokens
-        set_next_caller(accounts.bob);
-        assert_eq!(wlunes.burn(accounts.alice, 30), Ok(()));
-        
-        assert_eq!(wlunes.balance_of(accounts.alice), 70);
-        assert_eq!(wlunes.total_supply(), 70);
-        assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 20);
-    }
+#[ink::test]
+fn transfer_from_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Give Alice some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Alice approves Bob to spend 50 tokens
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    // Bob transfers from Alice to Charlie
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.transfer_from(accounts.alice, accounts.charlie, 30, vec![]), Ok(()));
+    
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 30);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 20);
+}
 
-    #[ink::test]
-    fn burn_insufficient_balance_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        set_next_caller(accounts.alice);
-        assert_eq!(wlunes.burn(accounts.alice, 100), Err(Error::InsufficientBalance));
-    }
+#[ink::test]
+fn transfer_from_insufficient_allowance_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.bob);
+    assert_eq!(
+        wlunes.transfer_from(accounts.alice, accounts.charlie, 100, vec![]),
+        Err(PSP22Error::InsufficientAllowance)
+    );
+}
+
+#[ink::test]
+fn burn_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Give Alice some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Alice burns her own tokens
+    assert_eq!(wlunes.burn(accounts.alice, 30), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.total_supply(), 70);
+}
+
+#[ink::test]
+fn burn_with_allowance_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Give Alice some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Alice approves Bob to burn her tokens
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    // Bob burns Alice's tokens
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.burn(accounts.alice, 30), Ok(()));
+    
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.total_supply(), 70);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 20);
+}
 
-    #[ink::test]
-    fn burn_insufficient_allowance_fails() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Give Alice some tokens
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Bob tries to burn Alice's tokens without allowance
-        set_next_caller(accounts.bob);
-        assert_eq!(wlunes.burn(accounts.alice, 30), Err(Error::InsufficientAllowance));
+#[ink::test]
+fn burn_insufficient_balance_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.burn(accounts.alice, 100), Err(PSP22Error::InsufficientBalance));
+}
+
+#[ink::test]
+fn burn_insufficient_allowance_fails() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Give Alice some tokens
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Bob tries to burn Alice's tokens without allowance
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.burn(accounts.alice, 30), Err(PSP22Error::InsufficientAllowance));
+}
+
+#[ink::test]
+fn invariant_total_supply_equals_contract_balance() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Initial state
+    assert_eq!(wlunes.total_supply(), 0);
+    
+    // After deposit
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Invariant: total_supply should equal the amount deposited
+    assert_eq!(wlunes.total_supply(), 100);
+    
+    // After another deposit
+    set_next_caller(accounts.bob);
+    set_balance(accounts.bob, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(50);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Invariant: total_supply should equal total deposited
+    assert_eq!(wlunes.total_supply(), 150);
+    
+    // After withdrawal
+    set_next_caller(accounts.alice);
+    set_balance(contract_id(), 10000000);
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw(30), Ok(()));
+    
+    // Invariant: total_supply should decrease by withdrawn amount
+    assert_eq!(wlunes.total_supply(), 120);
+}
+
+#[ink::test]
+fn events_are_emitted() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Test deposit event
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Deposit emits Transfer (mint) and Deposit
+    let emitted_events = test::recorded_events().collect::<Vec<_>>();
+    assert_eq!(emitted_events.len(), 2);
+    
+    // Test transfer event
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.bob, 30, vec![]), Ok(()));
+    
+    let emitted_events = test::recorded_events().collect::<Vec<_>>();
+    assert_eq!(emitted_events.len(), 3);
+    
+    // Test approval event
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    let emitted_events = test::recorded_events().collect::<Vec<_>>();
+    assert_eq!(emitted_events.len(), 4);
+}
+
+#[ink::test]
+fn transfer_from_emits_approval_with_remaining_allowance() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.transfer_from(accounts.alice, accounts.charlie, 30, vec![]), Ok(()));
+    
+    let approvals = decoded_events::<Approval>();
+    assert_eq!(approvals.len(), 2);
+    assert_approval(&approvals[0], accounts.alice, accounts.bob, 50);
+    assert_approval(&approvals[1], accounts.alice, accounts.bob, 20);
+    
+    let transfers = decoded_events::<Transfer>();
+    let last = transfers.last().unwrap();
+    assert_eq!(last.from, Some(accounts.alice));
+    assert_eq!(last.to, Some(accounts.charlie));
+    assert_eq!(last.value, 30);
+}
+
+#[ink::test]
+fn burn_with_allowance_emits_approval() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    // Burning own tokens does not touch allowances
+    assert_eq!(wlunes.burn(accounts.alice, 10), Ok(()));
+    assert_eq!(decoded_events::<Approval>().len(), 1);
+    
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.burn(accounts.alice, 50), Ok(()));
+    
+    let approvals = decoded_events::<Approval>();
+    assert_eq!(approvals.len(), 2);
+    assert_approval(&approvals[1], accounts.alice, accounts.bob, 0);
+}
+
+#[ink::test]
+fn withdraw_from_emits_approval() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    set_balance(contract_id(), 10000000);
+    set_balance(accounts.charlie, 0);
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.withdraw_from(accounts.alice, accounts.charlie, 40), Ok(()));
+    assert_eq!(get_balance(accounts.charlie), 40);
+    
+    let approvals = decoded_events::<Approval>();
+    assert_eq!(approvals.len(), 2);
+    assert_approval(&approvals[1], accounts.alice, accounts.bob, 10);
+}
+
+#[ink::test]
+fn overflow_protection_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    // Amounts large enough to overflow total_supply are rejected up front
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, u128::MAX);
+    test::set_value_transferred::<DefaultEnvironment>(u128::MAX);
+    
+    assert_eq!(wlunes.deposit(), Err(Error::AmountTooLarge));
+    assert_eq!(wlunes.total_supply(), 0);
+}
+
+#[ink::test]
+fn infinite_allowance_is_not_decremented() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, u128::MAX), Ok(()));
+    
+    set_balance(contract_id(), 10000000);
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.transfer_from(accounts.alice, accounts.charlie, 30, vec![]), Ok(()));
+    assert_eq!(wlunes.burn(accounts.alice, 20), Ok(()));
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw_from(accounts.alice, accounts.bob, 10), Ok(()));
+    
+    assert_eq!(wlunes.balance_of(accounts.alice), 40);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), u128::MAX);
+    
+    // Only the initial approve emitted Approval
+    assert_eq!(decoded_events::<Approval>().len(), 1);
+}
+
+#[ink::test]
+fn permit_with_sr25519_works() {
+    let mut wlunes = new_signing_wlunes();
+    let accounts = default_accounts();
+    let keypair = sr25519_keypair(7);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    let message = permit_message(&wlunes, owner, accounts.bob, 500, 1000);
+    let signature = keypair.sign_simple(b"substrate", &message).to_bytes();
+    
+    // Anyone can submit the signed approval
+    set_next_caller(accounts.charlie);
+    assert_eq!(wlunes.permit(owner, accounts.bob, 500, 1000, Signature::Sr25519(signature)), Ok(()));
+    assert_eq!(wlunes.allowance(owner, accounts.bob), 500);
+    assert_eq!(wlunes.nonces(owner), 1);
+    assert_approval(&decoded_events::<Approval>()[0], owner, accounts.bob, 500);
+    
+    // The same signature cannot be replayed
+    assert_eq!(
+        wlunes.permit(owner, accounts.bob, 500, 1000, Signature::Sr25519(signature)),
+        Err(Error::InvalidSignature)
+    );
+}
+
+#[ink::test]
+fn permit_with_ecdsa_works() {
+    let mut wlunes = new_signing_wlunes();
+    let accounts = default_accounts();
+    let secret = secp256k1::SecretKey::from_slice(&[9u8; 32]).unwrap();
+    let public = secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &secret);
+    let mut owner = [0u8; 32];
+    ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public.serialize(), &mut owner);
+    let owner = AccountId::from(owner);
+    
+    let message = permit_message(&wlunes, owner, accounts.bob, 42, 1000);
+    let (recovery_id, compact) = secp256k1::SECP256K1
+        .sign_ecdsa_recoverable(&secp256k1::Message::from_digest(message), &secret)
+        .serialize_compact();
+    let mut signature = [0u8; 65];
+    signature[..64].copy_from_slice(&compact);
+    signature[64] = recovery_id.to_i32() as u8;
+    
+    assert_eq!(wlunes.permit(owner, accounts.bob, 42, 1000, Signature::Ecdsa(signature)), Ok(()));
+    assert_eq!(wlunes.allowance(owner, accounts.bob), 42);
+    assert_eq!(wlunes.nonces(owner), 1);
+}
+
+#[ink::test]
+fn permit_rejects_expired_and_forged_signatures() {
+    let mut wlunes = new_signing_wlunes();
+    let accounts = default_accounts();
+    let keypair = sr25519_keypair(7);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    // Signature for a different value
+    let message = permit_message(&wlunes, owner, accounts.bob, 1, 1000);
+    let signature = keypair.sign_simple(b"substrate", &message).to_bytes();
+    assert_eq!(
+        wlunes.permit(owner, accounts.bob, 1_000_000, 1000, Signature::Sr25519(signature)),
+        Err(Error::InvalidSignature)
+    );
+    
+    // Signed by someone other than the owner
+    assert_eq!(
+        wlunes.permit(accounts.alice, accounts.bob, 1, 1000, Signature::Sr25519(signature)),
+        Err(Error::InvalidSignature)
+    );
+    
+    // Past the deadline
+    test::advance_block::<DefaultEnvironment>();
+    let deadline = 0;
+    let message = permit_message(&wlunes, owner, accounts.bob, 1, deadline);
+    let signature = keypair.sign_simple(b"substrate", &message).to_bytes();
+    assert_eq!(
+        wlunes.permit(owner, accounts.bob, 1, deadline, Signature::Sr25519(signature)),
+        Err(Error::SignatureExpired)
+    );
+    
+    assert_eq!(wlunes.allowance(owner, accounts.bob), 0);
+    assert_eq!(wlunes.nonces(owner), 0);
+}
+
+#[ink::test]
+fn signed_messages_require_chain_id() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    let keypair = sr25519_keypair(7);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    let message = permit_message(&wlunes, owner, accounts.bob, 500, 1000);
+    let signature = keypair.sign_simple(b"substrate", &message).to_bytes();
+    assert_eq!(
+        wlunes.permit(owner, accounts.bob, 500, 1000, Signature::Sr25519(signature)),
+        Err(Error::ChainIdNotSet)
+    );
+    
+    let intent = SignedIntent {
+        owner,
+        action: SignedAction::Approve { spender: accounts.bob, value: 500 },
+        nonce: 0,
+        deadline: 1000,
+        max_fee: 0,
+    };
+    let signature = sign_intent(&wlunes, &keypair, &intent);
+    assert_eq!(wlunes.execute_signed(intent, 0, signature), Err(Error::ChainIdNotSet));
+    assert_eq!(wlunes.nonces(owner), 0);
+}
+
+#[ink::test]
+fn execute_signed_transfer_pays_relayer() {
+    let mut wlunes = new_signing_wlunes();
+    let accounts = default_accounts();
+    let keypair = sr25519_keypair(11);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    set_next_caller(owner);
+    set_balance(owner, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    skip_cooldown();
+    
+    let intent = SignedIntent {
+        owner,
+        action: SignedAction::Transfer { to: accounts.bob, value: 60 },
+        nonce: 0,
+        deadline: 1000,
+        max_fee: 5,
+    };
+    let signature = sign_intent(&wlunes, &keypair, &intent);
+    
+    // Relayer asks for more than the signed maximum
+    set_next_caller(accounts.charlie);
+    assert_eq!(wlunes.execute_signed(intent.clone(), 6, signature.clone()), Err(Error::FeeTooHigh));
+    
+    assert_eq!(wlunes.execute_signed(intent.clone(), 3, signature.clone()), Ok(()));
+    assert_eq!(wlunes.balance_of(owner), 37);
+    assert_eq!(wlunes.balance_of(accounts.bob), 60);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 3);
+    assert_eq!(wlunes.nonces(owner), 1);
+    
+    let executed = decoded_events::<SignedIntentExecuted>();
+    assert_eq!(executed.len(), 1);
+    assert_eq!(executed[0].relayer, accounts.charlie);
+    assert_eq!(executed[0].fee, 3);
+    
+    // Replays use a stale nonce
+    assert_eq!(wlunes.execute_signed(intent, 3, signature), Err(Error::InvalidNonce));
+}
+
+#[ink::test]
+fn execute_signed_withdraw_works() {
+    let mut wlunes = new_signing_wlunes();
+    let accounts = default_accounts();
+    let keypair = sr25519_keypair(12);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    set_next_caller(owner);
+    set_balance(owner, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    set_balance(accounts.eve, 0);
+    
+    let intent = SignedIntent {
+        owner,
+        action: SignedAction::Withdraw { recipient: accounts.eve, amount: 90 },
+        nonce: 0,
+        deadline: 1000,
+        max_fee: 10,
+    };
+    let signature = sign_intent(&wlunes, &keypair, &intent);
+    
+    // Signature must cover the exact intent
+    let mut tampered = intent.clone();
+    tampered.max_fee = 100;
+    set_next_caller(accounts.charlie);
+    assert_eq!(wlunes.execute_signed(tampered, 10, signature.clone()), Err(Error::InvalidSignature));
+    
+    skip_cooldown();
+    assert_eq!(wlunes.execute_signed(intent, 10, signature), Ok(()));
+    assert_eq!(wlunes.balance_of(owner), 0);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 10);
+    assert_eq!(wlunes.total_supply(), 10);
+    assert_eq!(get_balance(accounts.eve), 90);
+}
+
+#[ink::test]
+fn execute_signed_charges_the_owner_bucket() {
+    let accounts = default_accounts();
+    let config = SecurityConfig {
+        rate_limit_burst: 2,
+        ..SecurityConfig::default()
+    };
+    let mut wlunes = Wlunes::new_with_config(Hash::from([0x42; 32]), 0, accounts.alice, config);
+    let keypair = sr25519_keypair(13);
+    let owner = AccountId::from(keypair.public.to_bytes());
+    
+    set_next_caller(owner);
+    set_balance(owner, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    skip_cooldown();
+    
+    let intent = |nonce, action| SignedIntent {
+        owner,
+        action,
+        nonce,
+        deadline: 10_000,
+        max_fee: 2,
+    };
+    
+    // The owner's last token pays for a withdrawal and its relayer fee
+    let withdraw = intent(0, SignedAction::Withdraw { recipient: owner, amount: 10 });
+    let signature = sign_intent(&wlunes, &keypair, &withdraw);
+    set_next_caller(accounts.charlie);
+    assert_eq!(wlunes.execute_signed(withdraw, 2, signature), Ok(()));
+    
+    let transfer = intent(1, SignedAction::Transfer { to: accounts.bob, value: 10 });
+    let signature = sign_intent(&wlunes, &keypair, &transfer);
+    skip_cooldown();
+    assert_eq!(wlunes.execute_signed(transfer, 1, signature), Err(Error::OperationBlocked));
+    
+    // The relayer's own bucket is untouched
+    assert_eq!(wlunes.transfer(accounts.bob, 1, vec![]), Ok(()));
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.bob, 1, vec![]), Ok(()));
+}
+
+#[ink::test]
+fn flash_loan_queries_work() {
+    let accounts = default_accounts();
+    let wlunes = Wlunes::new_with_flash_fee(Default::default(), 9, accounts.django);
+    
+    assert_eq!(wlunes.flash_fee(10_000), 9);
+    assert_eq!(wlunes.flash_fee(1_000_000), 900);
+    assert_eq!(wlunes.flash_fee(u128::MAX), u128::MAX / 10_000 * 9 + (u128::MAX % 10_000) * 9 / 10_000);
+    assert_eq!(wlunes.max_flash_loan(), u128::MAX / 2);
+    
+    // Default constructor charges no fee
+    assert_eq!(Wlunes::new().flash_fee(1_000_000), 0);
+}
+
+#[ink::test]
+fn flash_loan_rejects_invalid_requests() {
+    let accounts = default_accounts();
+    let mut wlunes = Wlunes::new_with_flash_fee(Default::default(), 9, accounts.django);
+    
+    set_next_caller(accounts.alice);
+    assert_eq!(wlunes.flash_loan(accounts.bob, 0, vec![]), Err(Error::ZeroAmount));
+    assert_eq!(wlunes.flash_loan(accounts.bob, u128::MAX, vec![]), Err(Error::AmountTooLarge));
+    
+    // Receiver must be a contract implementing the callback
+    assert_eq!(wlunes.flash_loan(accounts.bob, 1000, vec![]), Err(Error::FlashLoanFailed));
+    assert_eq!(wlunes.total_supply(), 0);
+}
+
+#[ink::test]
+fn flash_borrow_native_rejects_invalid_requests() {
+    let accounts = default_accounts();
+    let mut wlunes = Wlunes::new_with_flash_fee(Default::default(), 9, accounts.django);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    set_balance(contract_id(), 10000000);
+    
+    assert_eq!(wlunes.flash_borrow_native(accounts.bob, 0, vec![]), Err(Error::ZeroAmount));
+    assert_eq!(
+        wlunes.flash_borrow_native(accounts.bob, 10000001, vec![]),
+        Err(Error::InsufficientBalance)
+    );
+    
+    // Receiver must be a contract implementing the callback
+    assert_eq!(wlunes.flash_borrow_native(accounts.bob, 1000, vec![]), Err(Error::FlashLoanFailed));
+    assert_eq!(get_balance(contract_id()), 10000000);
+    assert_eq!(wlunes.total_supply(), 100);
+}
+
+#[ink::test]
+fn batch_transfer_works() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    let recipients = vec![(accounts.bob, 10), (accounts.charlie, 20), (accounts.bob, 5)];
+    skip_cooldown();
+    assert_eq!(wlunes.batch_transfer(recipients, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 65);
+    assert_eq!(wlunes.balance_of(accounts.bob), 15);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 20);
+    assert_eq!(wlunes.total_supply(), 100);
+    
+    // One Transfer per recipient (plus the deposit mint)
+    let transfers = decoded_events::<Transfer>();
+    assert_eq!(transfers.len(), 4);
+    assert_eq!(transfers[2].to, Some(accounts.charlie));
+    assert_eq!(transfers[2].value, 20);
+}
+
+#[ink::test]
+fn batch_transfer_is_all_or_nothing() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    let zero = AccountId::from([0u8; 32]);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    skip_cooldown();
+    assert_eq!(
+        wlunes.batch_transfer(vec![(accounts.bob, 60), (accounts.charlie, 41)], vec![]),
+        Err(PSP22Error::InsufficientBalance)
+    );
+    skip_cooldown();
+    assert_eq!(
+        wlunes.batch_transfer(vec![(accounts.bob, 10), (zero, 10)], vec![]),
+        Err(PSP22Error::ZeroRecipientAddress)
+    );
+    skip_cooldown();
+    assert_eq!(
+        wlunes.batch_transfer(vec![(accounts.bob, 0); MAX_BATCH_SIZE + 1], vec![]),
+        Err(PSP22Error::Custom("BatchTooLarge".into()))
+    );
+    assert_eq!(wlunes.balance_of(accounts.alice), 100);
+    assert_eq!(wlunes.balance_of(accounts.bob), 0);
+}
+
+#[ink::test]
+fn batch_transfer_from_spends_allowance_once() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
+    
+    set_next_caller(accounts.bob);
+    assert_eq!(
+        wlunes.batch_transfer_from(accounts.alice, vec![(accounts.charlie, 30), (accounts.django, 30)], vec![]),
+        Err(PSP22Error::InsufficientAllowance)
+    );
+    skip_cooldown();
+    assert_eq!(
+        wlunes.batch_transfer_from(accounts.alice, vec![(accounts.charlie, 30), (accounts.django, 10)], vec![]),
+        Ok(())
+    );
+    assert_eq!(wlunes.balance_of(accounts.alice), 60);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 30);
+    assert_eq!(wlunes.balance_of(accounts.django), 10);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 10);
+    
+    let approvals = decoded_events::<Approval>();
+    assert_eq!(approvals.len(), 2);
+    assert_approval(&approvals[1], accounts.alice, accounts.bob, 10);
+}
+
+#[ink::test]
+fn multicall_wraps_and_runs_calls() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    let calls = vec![
+        Call::Approve { spender: accounts.bob, value: 30 },
+        Call::Transfer { to: accounts.charlie, value: 20, data: vec![] },
+        Call::Transfer { to: accounts.django, value: 10, data: vec![] },
+    ];
+    assert_eq!(wlunes.multicall(calls), Ok(vec![Ok(()), Ok(()), Ok(())]));
+    
+    assert_eq!(wlunes.total_supply(), 100);
+    assert_eq!(wlunes.balance_of(accounts.alice), 70);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 20);
+    assert_eq!(wlunes.balance_of(accounts.django), 10);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 30);
+    assert_eq!(decoded_events::<Deposit>().len(), 1);
+    assert_eq!(decoded_events::<Transfer>().len(), 3);
+    
+    // Withdraw + transfer in one batch
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    let calls = vec![
+        Call::Withdraw { recipient: accounts.alice, amount: 40 },
+        Call::Transfer { to: accounts.charlie, value: 30, data: vec![] },
+    ];
+    skip_cooldown();
+    assert_eq!(wlunes.multicall(calls), Ok(vec![Ok(()), Ok(())]));
+    assert_eq!(wlunes.total_supply(), 60);
+    assert_eq!(wlunes.balance_of(accounts.alice), 0);
+    assert_eq!(wlunes.balance_of(accounts.charlie), 50);
+}
+
+#[ink::test]
+fn multicall_reports_failing_call() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    let calls = vec![
+        Call::Transfer { to: accounts.bob, value: 60, data: vec![] },
+        Call::Transfer { to: accounts.charlie, value: 60, data: vec![] },
+    ];
+    skip_cooldown();
+    assert_eq!(
+        wlunes.multicall(calls),
+        Err(MulticallError {
+            results: vec![Ok(()), Err(Error::InsufficientBalance)],
+            error: Error::InsufficientBalance,
+        })
+    );
+    
+    set_next_caller(accounts.bob);
+    let calls = vec![Call::TransferFrom { from: accounts.alice, to: accounts.bob, value: 1, data: vec![] }];
+    assert_eq!(
+        wlunes.multicall(calls),
+        Err(MulticallError {
+            results: vec![Err(Error::InsufficientAllowance)],
+            error: Error::InsufficientAllowance,
+        })
+    );
+    
+    let calls = vec![Call::Approve { spender: accounts.charlie, value: 0 }; MAX_BATCH_SIZE + 1];
+    assert_eq!(
+        wlunes.multicall(calls),
+        Err(MulticallError { results: vec![], error: Error::BatchTooLarge })
+    );
+}
+
+#[ink::test]
+fn deposit_and_call_and_withdraw_and_call_reject_invalid_input() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    let zero = AccountId::from([0u8; 32]);
+    let selector = ink::selector_bytes!("swap");
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit_and_call(zero, selector, vec![]), Err(Error::ZeroAddress));
+    
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    assert_eq!(wlunes.deposit_and_call(accounts.bob, selector, vec![]), Err(Error::ZeroAmount));
+    assert_eq!(wlunes.withdraw_and_call(0, accounts.bob, selector, vec![]), Err(Error::ZeroAmount));
+    assert_eq!(wlunes.withdraw_and_call(10, accounts.bob, selector, vec![]), Err(Error::InsufficientBalance));
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw_and_call(10, zero, selector, vec![]), Err(Error::ZeroAddress));
+    assert_eq!(wlunes.total_supply(), 0);
+}
+
+#[ink::test]
+fn transfer_to_contract_address_unwraps() {
+    let accounts = default_accounts();
+    // The default callee is Alice; give the contract its own address
+    test::set_callee::<DefaultEnvironment>(accounts.frank);
+    let mut wlunes = Wlunes::new();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(contract_id(), 40, vec![]), Ok(()));
+    assert_eq!(wlunes.total_supply(), 60);
+    assert_eq!(wlunes.balance_of(accounts.alice), 60);
+    assert_eq!(wlunes.balance_of(contract_id()), 0);
+    assert_eq!(get_balance(accounts.alice), 10000040);
+    
+    // transfer_from unwraps to the token owner, not the spender
+    assert_eq!(wlunes.approve(accounts.bob, 20), Ok(()));
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.transfer_from(accounts.alice, contract_id(), 20, vec![]), Ok(()));
+    assert_eq!(wlunes.total_supply(), 40);
+    assert_eq!(wlunes.allowance(accounts.alice, accounts.bob), 0);
+    assert_eq!(get_balance(accounts.alice), 10000060);
+    
+    let withdrawals = decoded_events::<Withdrawal>();
+    assert_eq!(withdrawals.len(), 2);
+    assert_eq!(withdrawals[1].account, accounts.alice);
+    assert_eq!(withdrawals[1].recipient, accounts.alice);
+}
+
+#[ink::test]
+fn batch_transfer_to_contract_address_unwraps() {
+    let accounts = default_accounts();
+    test::set_callee::<DefaultEnvironment>(accounts.frank);
+    let mut wlunes = Wlunes::new();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    skip_cooldown();
+    assert_eq!(wlunes.batch_transfer(vec![(accounts.bob, 10), (contract_id(), 30)], vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.alice), 60);
+    assert_eq!(wlunes.balance_of(accounts.bob), 10);
+    assert_eq!(wlunes.balance_of(contract_id()), 0);
+    assert_eq!(wlunes.total_supply(), 70);
+    assert_eq!(get_balance(accounts.alice), 10000030);
+    
+    // batch_transfer_from unwraps to the token owner
+    assert_eq!(wlunes.approve(accounts.bob, 20), Ok(()));
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.batch_transfer_from(accounts.alice, vec![(contract_id(), 20)], vec![]), Ok(()));
+    assert_eq!(wlunes.total_supply(), 50);
+    assert_eq!(get_balance(accounts.alice), 10000050);
+}
+
+#[ink::test]
+fn transfer_to_contract_address_is_rate_limited_once() {
+    let accounts = default_accounts();
+    test::set_callee::<DefaultEnvironment>(accounts.frank);
+    let config = SecurityConfig {
+        rate_limit_burst: 2,
+        ..SecurityConfig::default()
+    };
+    let mut wlunes = Wlunes::new_with_config(Default::default(), 0, accounts.alice, config);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    set_balance(contract_id(), 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    // The unwrap does not take a second token
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(contract_id(), 40, vec![]), Ok(()));
+    assert_eq!(wlunes.total_supply(), 60);
+    skip_cooldown();
+    assert_eq!(
+        wlunes.transfer(accounts.bob, 1, vec![]),
+        Err(PSP22Error::Custom("OperationBlocked".into()))
+    );
+}
+
+#[ink::test]
+fn skim_pays_surplus_to_fee_recipient() {
+    let accounts = default_accounts();
+    test::set_callee::<DefaultEnvironment>(accounts.frank);
+    let mut wlunes = Wlunes::new_with_flash_fee(Default::default(), 0, accounts.eve);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    set_balance(accounts.eve, 10000000);
+    // Existential deposit plus the deposited LUNES
+    set_balance(contract_id(), 11000000);
+    test::set_value_transferred::<DefaultEnvironment>(10000000);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    // Backing plus an unminted balance transfer
+    set_balance(contract_id(), 11000050);
+    assert_eq!(wlunes.surplus(), 50);
+    
+    // Burning keeps the native LUNES as surplus
+    assert_eq!(wlunes.burn(accounts.alice, 30), Ok(()));
+    assert_eq!(wlunes.surplus(), 80);
+    
+    // Anyone can skim, the beneficiary is fixed
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.skim(), Ok(()));
+    assert_eq!(wlunes.surplus(), 0);
+    assert_eq!(get_balance(contract_id()), 10999970);
+    assert_eq!(get_balance(accounts.eve), 10000080);
+    assert_eq!(wlunes.total_supply(), 9999970);
+    
+    let skims = decoded_events::<Skim>();
+    assert_eq!(skims.len(), 1);
+    assert_eq!(skims[0].caller, accounts.bob);
+    assert_eq!(skims[0].beneficiary, accounts.eve);
+    assert_eq!(skims[0].amount, 80);
+    
+    assert_eq!(wlunes.skim(), Err(Error::ZeroAmount));
+}
+
+#[ink::test]
+fn withdrawal_fallback_is_opt_in() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.bob);
+    assert!(!wlunes.withdrawal_fallback(accounts.bob));
+    assert_eq!(wlunes.set_withdrawal_fallback(true), Ok(()));
+    assert!(wlunes.withdrawal_fallback(accounts.bob));
+    assert!(!wlunes.withdrawal_fallback(accounts.alice));
+    assert_eq!(wlunes.set_withdrawal_fallback(false), Ok(()));
+    assert!(!wlunes.withdrawal_fallback(accounts.bob));
+    
+    assert_eq!(wlunes.pending_withdrawal(accounts.bob), 0);
+    assert_eq!(wlunes.claim_withdrawal(), Err(Error::ZeroAmount));
+}
+
+#[ink::test]
+fn withdraw_reports_insufficient_reserve_before_burning() {
+    let accounts = default_accounts();
+    test::set_callee::<DefaultEnvironment>(accounts.frank);
+    let mut wlunes = Wlunes::new();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    // Existential deposit (1_000_000) plus the deposited LUNES
+    set_balance(contract_id(), 1000100);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    // Only 50 LUNES above the existential deposit remain
+    set_balance(contract_id(), 1000050);
+    skip_cooldown();
+    assert_eq!(wlunes.withdraw(60), Err(Error::InsufficientReserve));
+    assert_eq!(wlunes.balance_of(accounts.alice), 100);
+    assert_eq!(wlunes.total_supply(), 100);
+}
+
+#[ink::test]
+fn rate_limit_bucket_refills_over_time() {
+    let mut wlunes = new_rate_limited_wlunes(3000, 3);
+    let accounts = default_accounts();
+    test::set_block_timestamp::<DefaultEnvironment>(10_000);
+    
+    // Deposits, transfers and withdrawals draw from the same bucket
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    assert_eq!(wlunes.transfer(accounts.bob, 10, vec![]), Ok(()));
+    assert_eq!(wlunes.withdraw(10), Ok(()));
+    assert_eq!(
+        wlunes.transfer(accounts.bob, 10, vec![]),
+        Err(PSP22Error::Custom("OperationBlocked".into()))
+    );
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    
+    // Other accounts have their own bucket
+    set_next_caller(accounts.bob);
+    assert_eq!(wlunes.transfer(accounts.charlie, 5, vec![]), Ok(()));
+    
+    // One token per second refills
+    set_next_caller(accounts.alice);
+    test::set_block_timestamp::<DefaultEnvironment>(10_500);
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    test::set_block_timestamp::<DefaultEnvironment>(11_000);
+    assert_eq!(wlunes.withdraw(10), Ok(()));
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    
+    // Time towards the next token carries over a refill
+    test::set_block_timestamp::<DefaultEnvironment>(12_500);
+    assert_eq!(wlunes.withdraw(10), Ok(()));
+    test::set_block_timestamp::<DefaultEnvironment>(13_000);
+    assert_eq!(wlunes.withdraw(10), Ok(()));
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    
+    // The bucket never holds more than the burst size
+    test::set_block_timestamp::<DefaultEnvironment>(100_000);
+    for _ in 0..3 {
+        assert_eq!(wlunes.transfer(accounts.bob, 1, vec![]), Ok(()));
     }
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+}
+
+#[ink::test]
+fn cooldown_applies_to_every_rate_limited_operation() {
+    let mut wlunes = Wlunes::new();
+    let accounts = default_accounts();
+    test::set_block_timestamp::<DefaultEnvironment>(10_000);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    // Transfers, batches, unwraps and multicalls all wait for the cooldown
+    let blocked = || Err(PSP22Error::Custom("OperationBlocked".into()));
+    assert_eq!(wlunes.transfer(accounts.bob, 10, vec![]), blocked());
+    assert_eq!(wlunes.transfer(contract_id(), 10, vec![]), blocked());
+    assert_eq!(wlunes.batch_transfer(vec![(accounts.bob, 10)], vec![]), blocked());
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    assert_eq!(
+        wlunes.multicall(vec![Call::Transfer { to: accounts.bob, value: 10, data: vec![] }]).map_err(|e| e.error),
+        Err(Error::OperationBlocked)
+    );
+    
+    // Other accounts have their own cooldown
+    set_next_caller(accounts.bob);
+    set_balance(accounts.bob, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(50);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    
+    // Blocked attempts do not restart the cooldown
+    set_next_caller(accounts.alice);
+    test::set_block_timestamp::<DefaultEnvironment>(10_999);
+    assert_eq!(wlunes.withdraw(10), Err(Error::OperationBlocked));
+    test::set_block_timestamp::<DefaultEnvironment>(11_000);
+    assert_eq!(wlunes.transfer(accounts.bob, 10, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.bob), 60);
+}
 
-    #[ink::test]
-    fn invariant_total_supply_equals_contract_balance() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Initial state
-        assert_eq!(wlunes.total_supply(), 0);
-        
-        // After deposit
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Invariant: total_supply should equal the amount deposited
-        assert_eq!(wlunes.total_supply(), 100);
-        
-        // After another deposit
-        set_next_caller(accounts.bob);
-        set_balance(accounts.bob, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(50);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Invariant: total_supply should equal total deposited
-        assert_eq!(wlunes.total_supply(), 150);
-        
-        // After withdrawal
-        set_next_caller(accounts.alice);
-        set_balance(contract_id(), 150);
-        assert_eq!(wlunes.withdraw(30), Ok(()));
-        
-        // Invariant: total_supply should decrease by withdrawn amount
-        assert_eq!(wlunes.total_supply(), 120);
+#[ink::test]
+fn rate_limit_burst_zero_disables_limiter() {
+    let mut wlunes = new_rate_limited_wlunes(3000, 0);
+    let accounts = default_accounts();
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    for _ in 0..10 {
+        assert_eq!(wlunes.transfer(accounts.bob, 1, vec![]), Ok(()));
     }
+}
 
-    #[ink::test]
-    fn events_are_emitted() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Test deposit event
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, 1000);
-        test::set_value_transferred::<DefaultEnvironment>(100);
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        let emitted_events = test::recorded_events().collect::<Vec<_>>();
-        assert_eq!(emitted_events.len(), 1);
-        
-        // Test transfer event
-        assert_eq!(wlunes.transfer(accounts.bob, 30, vec![]), Ok(()));
-        
-        let emitted_events = test::recorded_events().collect::<Vec<_>>();
-        assert_eq!(emitted_events.len(), 2);
-        
-        // Test approval event
-        assert_eq!(wlunes.approve(accounts.bob, 50), Ok(()));
-        
-        let emitted_events = test::recorded_events().collect::<Vec<_>>();
-        assert_eq!(emitted_events.len(), 3);
+#[ink::test]
+fn contract_callers_are_not_rate_limited() {
+    let mut wlunes = new_rate_limited_wlunes(3000, 1);
+    let accounts = default_accounts();
+    let router = accounts.django;
+    test::set_contract::<DefaultEnvironment>(router);
+    
+    // Alice spends her only token on the deposit
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(100);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    assert_eq!(wlunes.approve(router, 100), Ok(()));
+    
+    // A router acting for many users is never charged
+    set_next_caller(router);
+    for _ in 0..10 {
+        assert_eq!(wlunes.transfer_from(accounts.alice, accounts.charlie, 5, vec![]), Ok(()));
     }
+    set_balance(router, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(50);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(0);
+    for _ in 0..10 {
+        assert_eq!(wlunes.transfer(accounts.bob, 5, vec![]), Ok(()));
+    }
+    assert_eq!(wlunes.balance_of(accounts.charlie), 50);
+    assert_eq!(wlunes.balance_of(accounts.bob), 50);
+    
+    // Alice's own calls stay limited
+    set_next_caller(accounts.alice);
+    assert_eq!(
+        wlunes.transfer(accounts.bob, 1, vec![]),
+        Err(PSP22Error::Custom("OperationBlocked".into()))
+    );
+}
+
+#[ink::test]
+fn new_with_config_applies_deploy_time_limits() {
+    let accounts = default_accounts();
+    let config = SecurityConfig {
+        max_transaction_amount: 1_000,
+        suspicious_amount: 500,
+        max_total_supply: 10_000,
+        ..SecurityConfig::default()
+    };
+    let mut wlunes = Wlunes::new_with_config(Default::default(), 0, accounts.alice, config.clone());
+    assert_eq!(wlunes.security_config(), config);
+    assert_eq!(wlunes.max_flash_loan(), 10_000);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    
+    // Pattern threshold blocks large wraps
+    test::set_value_transferred::<DefaultEnvironment>(600);
+    assert_eq!(wlunes.deposit(), Err(Error::AmountTooLarge));
+    test::set_value_transferred::<DefaultEnvironment>(400);
+    skip_cooldown();
+    assert_eq!(wlunes.deposit(), Ok(()));
+    test::set_value_transferred::<DefaultEnvironment>(400);
+    skip_cooldown();
+    assert_eq!(wlunes.deposit(), Ok(()));
+    
+    // Per-transaction cap applies to transfers
+    assert_eq!(
+        wlunes.transfer(accounts.bob, 1_001, vec![]),
+        Err(PSP22Error::Custom("AmountTooLarge".into()))
+    );
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.bob, 800, vec![]), Ok(()));
+}
 
-    #[ink::test]
-    fn overflow_protection_works() {
-        let mut wlunes = Wlunes::new();
-        let accounts = default_accounts();
-        
-        // Test that we can't overflow total_supply
-        set_next_caller(accounts.alice);
-        set_balance(accounts.alice, u128::MAX);
-        test::set_value_transferred::<DefaultEnvironment>(u128::MAX);
-        
-        // First deposit should work
-        assert_eq!(wlunes.deposit(), Ok(()));
-        
-        // Second deposit should fail due to overflow
-        test::set_value_transferred::<DefaultEnvironment>(1);
-        assert_eq!(wlunes.deposit(), Err(Error::Overflow));
+#[ink::test]
+fn alert_mode_emits_security_alert_without_blocking() {
+    let accounts = default_accounts();
+    let config = SecurityConfig {
+        suspicious_amount: 500,
+        pattern_mode: CheckMode::Alert,
+        rate_limit_burst: 1,
+        rate_limit_mode: CheckMode::Alert,
+        ..SecurityConfig::default()
+    };
+    let mut wlunes = Wlunes::new_with_config(Default::default(), 0, accounts.alice, config);
+    
+    set_next_caller(accounts.alice);
+    set_balance(accounts.alice, 10000000);
+    test::set_value_transferred::<DefaultEnvironment>(600);
+    assert_eq!(wlunes.deposit(), Ok(()));
+    skip_cooldown();
+    assert_eq!(wlunes.transfer(accounts.bob, 10, vec![]), Ok(()));
+    assert_eq!(wlunes.balance_of(accounts.bob), 10);
+    
+    let alerts = decoded_events::<SecurityAlert>();
+    assert_eq!(alerts.len(), 2);
+    assert_eq!(alerts[0].alert_type, 6); // Large transaction
+    assert_eq!(alerts[0].details, 600);
+    assert_eq!(alerts[1].alert_type, 4); // Rate limiting violation
+    assert_eq!(alerts[1].account, accounts.alice);
+}
+
+#[ink::test]
+fn recommended_weight_covers_every_operation() {
+    let wlunes = Wlunes::new();
+    for entries in [0, 1, 100] {
+        for operation in [
+            Operation::Deposit,
+            Operation::DepositFor,
+            Operation::Withdraw,
+            Operation::WithdrawTo,
+            Operation::WithdrawFrom,
+            Operation::SetWithdrawalFallback,
+            Operation::ClaimWithdrawal,
+            Operation::DepositAndCall,
+            Operation::WithdrawAndCall,
+            Operation::BatchTransfer { recipients: entries },
+            Operation::BatchTransferFrom { recipients: entries },
+            Operation::Multicall { calls: entries },
+            Operation::Permit,
+            Operation::ExecuteSigned,
+            Operation::FlashLoan,
+            Operation::FlashBorrowNative,
+            Operation::Skim,
+            Operation::Transfer,
+            Operation::TransferFrom,
+            Operation::Approve,
+            Operation::IncreaseAllowance,
+            Operation::DecreaseAllowance,
+            Operation::Burn,
+        ] {
+            let weight = wlunes.recommended_weight(operation);
+            assert!(weight.ref_time > 0 && weight.proof_size > 0);
+            assert!(0 < operation.min_ref_time() && operation.min_ref_time() < weight.ref_time);
+        }
     }
+    assert!(
+        wlunes.recommended_weight(Operation::Withdraw).ref_time
+            > wlunes.recommended_weight(Operation::Approve).ref_time
+    );
+
+    // Batches grow with their number of entries
+    let small = Operation::BatchTransfer { recipients: 1 };
+    let large = Operation::BatchTransfer { recipients: 100 };
+    assert!(wlunes.recommended_weight(large).ref_time > wlunes.recommended_weight(small).ref_time);
+    assert!(wlunes.recommended_weight(large).proof_size > wlunes.recommended_weight(small).proof_size);
+    assert!(large.min_ref_time() > small.min_ref_time());
 }
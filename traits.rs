@@ -0,0 +1,171 @@
+//! PSP22 trait definitions implemented by the WLUNES contract.
+//!
+//! Messages declared here use the canonical `PSP22::*`, `PSP22Metadata::*`
+//! and `PSP22Burnable::*` selectors, so any contract (for example a Lunex
+//! pool holding a `contract_ref!(PSP22)`) can call WLUNES without
+//! hand-written selectors. Build the crate with the `ink-as-dependency`
+//! feature to use these traits from another contract.
+
+use ink::prelude::string::String;
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+/// Balance type of the default environment used by WLUNES.
+pub type Balance = <ink::env::DefaultEnvironment as ink::env::Environment>::Balance;
+
+/// Standard PSP22 error type.
+///
+/// WLUNES-specific failures are reported as `Custom` with the stable code
+/// returned by `wlunes::Error::code` (for example `"ReentrancyDetected"`,
+/// `"GasLimitExceeded"` or `"OperationBlocked"`).
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Custom error type for implementation-based errors.
+    Custom(String),
+    /// Returned when an account does not have enough tokens to complete the operation.
+    InsufficientBalance,
+    /// Returned if there is not enough allowance to complete the operation.
+    InsufficientAllowance,
+    /// Returned if recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check failed.
+    SafeTransferCheckFailed(String),
+}
+
+/// Error returned by a `PSP22Receiver` that rejects a transfer.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22ReceiverError {
+    /// The receiver does not accept the transfer.
+    TransferRejected(String),
+}
+
+/// Error returned by a `PSP22FlashBorrower` that rejects a flash loan.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum FlashBorrowerError {
+    /// The borrower does not accept the loan.
+    FlashloanRejected(String),
+}
+
+/// PSP22 core interface.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message)]
+    fn total_supply(&self) -> Balance;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    /// Transfers `value` amount of tokens from the caller's account to account `to`.
+    #[ink(message)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Transfers `value` tokens on behalf of `from` to the account `to`.
+    #[ink(message)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Sets the allowance for `spender` to `value`.
+    #[ink(message)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+    /// Atomically increases the allowance granted to `spender` by `delta_value`.
+    #[ink(message)]
+    fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
+
+    /// Atomically decreases the allowance granted to `spender` by `delta_value`.
+    #[ink(message)]
+    fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
+}
+
+/// PSP22 Metadata extension.
+#[ink::trait_definition]
+pub trait PSP22Metadata {
+    /// Returns the token name.
+    #[ink(message)]
+    fn token_name(&self) -> Option<String>;
+
+    /// Returns the token symbol.
+    #[ink(message)]
+    fn token_symbol(&self) -> Option<String>;
+
+    /// Returns the token decimals.
+    #[ink(message)]
+    fn token_decimals(&self) -> u8;
+}
+
+/// PSP22 Burnable extension.
+#[ink::trait_definition]
+pub trait PSP22Burnable {
+    /// Burns `value` tokens from the `from` account.
+    #[ink(message)]
+    fn burn(&mut self, from: AccountId, value: Balance) -> Result<(), PSP22Error>;
+}
+
+/// Hook implemented by contracts that want to receive WLUNES.
+///
+/// WLUNES calls `before_received` whenever tokens are transferred to a
+/// contract account and reverts the transfer if the hook is missing or
+/// returns an error.
+#[ink::trait_definition]
+pub trait PSP22Receiver {
+    /// Called before `value` tokens are moved from `from` to this contract
+    /// on behalf of `operator`.
+    #[ink(message)]
+    fn before_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22ReceiverError>;
+}
+
+/// Callback implemented by contracts that take WLUNES flash loans.
+#[ink::trait_definition]
+pub trait PSP22FlashBorrower {
+    /// Called after `amount` WLUNES have been minted to this contract.
+    ///
+    /// Before returning, the borrower must approve the WLUNES contract for
+    /// `amount + fee`, otherwise the whole loan reverts.
+    #[ink(message)]
+    fn on_flash_loan(
+        &mut self,
+        initiator: AccountId,
+        amount: Balance,
+        fee: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), FlashBorrowerError>;
+}
+
+/// Callback implemented by contracts that borrow the native LUNES reserve.
+#[ink::trait_definition]
+pub trait NativeFlashBorrower {
+    /// Called with `amount` native LUNES attached.
+    ///
+    /// Before returning, the borrower must transfer `amount + fee` native
+    /// LUNES back to the WLUNES contract, otherwise the whole loan reverts.
+    #[ink(message, payable)]
+    fn on_flash_borrow(
+        &mut self,
+        initiator: AccountId,
+        amount: Balance,
+        fee: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), FlashBorrowerError>;
+}